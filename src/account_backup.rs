@@ -0,0 +1,43 @@
+use crate::{
+    game_manager::GameManagerDB,
+    local_levels::LocalLevelsDB,
+    serde::{
+        self as gd_serde,
+        DataWithHeader,
+        ser,
+        error::{DeError, DeResult, SerResult}
+    }
+};
+
+/// Separates the two saves in a backup, it can't appear in base64
+const SEPARATOR: char = ';';
+
+/// CCGameManager.dat and CCLocalLevels.dat data of an account backup
+pub type Backup = (DataWithHeader<GameManagerDB>, DataWithHeader<LocalLevelsDB>);
+
+/// Reads the payload the game uploads when backing up an account, both saves
+/// gzipped and base64 encoded, separated by `;`. The game version fields the
+/// game appends after them are ignored
+pub fn split(payload: &str) -> DeResult<Backup> {
+    let mut fields = payload.trim().split(SEPARATOR);
+    let (Some(game_manager), Some(local_levels)) = (fields.next(), fields.next()) else {
+        return Err(DeError::Custom(format!("backup has no '{SEPARATOR}' between the saves")))
+    };
+    Ok((
+        gd_serde::from_any_reader(game_manager.as_bytes())?,
+        gd_serde::from_any_reader(local_levels.as_bytes())?
+    ))
+}
+
+/// Payload of an account backup, as the game would upload it
+pub fn join(
+    game_manager: &DataWithHeader<GameManagerDB>,
+    local_levels: &DataWithHeader<LocalLevelsDB>
+) -> SerResult<String> {
+    let game_manager = ser::encode_base64(&ser::to_xml(game_manager)?)?;
+    let local_levels = ser::encode_base64(&ser::to_xml(local_levels)?)?;
+    Ok(format!("{game_manager}{SEPARATOR}{local_levels}"))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,63 @@
+use super::*;
+use crate::serde::Value;
+
+const GAME_MANAGER: &str = concat!(
+    r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+    "<k>bgVolume</k><r>0.5</r><k>sfxVolume</k><r>1</r><k>playerName</k><s>Tester</s>",
+    "<k>playerUserID</k><i>77</i><k>playerFrame</k><i>12</i><k>playerShip</k><i>3</i>",
+    "<k>playerBall</k><i>4</i><k>playerBird</k><i>5</i><k>playerDart</k><i>6</i>",
+    "<k>playerRobot</k><i>7</i><k>playerSpider</k><i>8</i><k>playerColor</k><i>9</i>",
+    "<k>playerColor2</k><i>12</i><k>playerStreak</k><i>2</i><k>playerDeathEffect</k><i>1</i>",
+    "<k>playerIconType</k><i>1</i><k>bootups</k><i>3</i><k>binaryVersion</k><i>35</i>",
+    "<k>resolution</k><i>-1</i><k>texQuality</k><i>3</i>",
+    "</dict></plist>"
+);
+
+const LOCAL_LEVELS: &str = concat!(
+    r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+    "<k>LLM_01</k><d><k>_isArr</k><t/>",
+    "<k>k_0</k><d><k>k2</k><s>First</s><k>k5</k><s>Tester</s><k>k4</k><s>H4sIAAAA</s></d>",
+    "</d><k>LLM_02</k><i>35</i>",
+    "</dict></plist>"
+);
+
+fn payload() -> String {
+    format!(
+        "{};{}",
+        ser::encode_base64(GAME_MANAGER.as_bytes()).unwrap(),
+        ser::encode_base64(LOCAL_LEVELS.as_bytes()).unwrap()
+    )
+}
+
+#[test]
+fn splits_payload() {
+    let (game_manager, local_levels) = split(&payload()).unwrap();
+    assert_eq!(game_manager.t.icon_kit().cube, 12);
+    assert_eq!(local_levels.t.levels()[0].name(), "First");
+}
+
+#[test]
+fn ignores_trailing_version_fields() {
+    let (game_manager, local_levels) = split(&format!("{};22;42;a;b", payload())).unwrap();
+    assert_eq!(game_manager.t.icon_kit().cube, 12);
+    assert_eq!(local_levels.t.levels()[0].name(), "First");
+}
+
+#[test]
+fn joins_what_it_splits() {
+    let (game_manager, local_levels) = split(&payload()).unwrap();
+    let joined = join(&game_manager, &local_levels).unwrap();
+    assert_eq!(joined.matches(';').count(), 1);
+    assert!(joined.starts_with("H4sI"));
+
+    let (game_manager_again, local_levels_again) = split(&joined).unwrap();
+    assert_eq!(game_manager_again.header, game_manager.header);
+    assert_eq!(local_levels_again.t, local_levels.t);
+}
+
+#[test]
+fn rejects_payload_without_separator() {
+    let game_manager = ser::encode_base64(GAME_MANAGER.as_bytes()).unwrap();
+    assert!(matches!(split(&game_manager), Err(DeError::Custom(_))));
+    assert!(gd_serde::from_any_reader::<Value, _>(game_manager.as_bytes()).is_ok());
+}
@@ -0,0 +1,36 @@
+use std::{env, process};
+use gdparser_temp::serde::{self as gd_serde, DataWithHeader, Value};
+
+fn main() {
+    let mut json = false;
+    let mut paths = vec![];
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ => paths.push(arg)
+        }
+    }
+    let [a, b] = &paths[..] else {
+        eprintln!("usage: gd-diff [--json] <old save> <new save>");
+        process::exit(2);
+    };
+
+    let load = |path: &String| -> Value {
+        match gd_serde::from_file::<Value, _>(path) {
+            Ok(DataWithHeader { t, .. }) => t,
+            Err(err) => {
+                eprintln!("{path}: {err}");
+                process::exit(1);
+            }
+        }
+    };
+    let changes = gd_serde::diff(&load(a), &load(b));
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&changes).expect("changes serialize to json"));
+    } else {
+        for change in &changes {
+            println!("{change}");
+        }
+    }
+}
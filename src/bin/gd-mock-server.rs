@@ -0,0 +1,68 @@
+use std::{env, path::Path, process};
+use gdparser_temp::{
+    game_manager::GameManagerDB,
+    local_levels::LocalLevelsDB,
+    mock_server::MockServer,
+    serde as gd_serde
+};
+use tiny_http::{Response, Server};
+
+const DEFAULT_PORT: u16 = 8080;
+
+fn main() {
+    let mut port = DEFAULT_PORT;
+    let mut source = None;
+    let mut songs = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => port = args.next().and_then(|port| port.parse().ok()).unwrap_or_else(|| usage()),
+            "--songs" => songs = Some(args.next().unwrap_or_else(|| usage())),
+            _ => source = Some(arg)
+        }
+    }
+    let Some(source) = source else { usage() };
+
+    let loaded = if Path::new(&source).is_dir() {
+        MockServer::from_gmd_dir(&source)
+    } else {
+        gd_serde::from_file::<LocalLevelsDB, _>(&source)
+            .map(|local_levels| MockServer::from_local_levels(&local_levels.t))
+    };
+    let mut mock = loaded.unwrap_or_else(|err| {
+        eprintln!("{source}: {err}");
+        process::exit(1);
+    });
+    if let Some(songs) = songs {
+        let game_manager = gd_serde::from_file::<GameManagerDB, _>(&songs).unwrap_or_else(|err| {
+            eprintln!("{songs}: {err}");
+            process::exit(1);
+        });
+        mock = mock.with_songs(game_manager.t.song_library().clone());
+    }
+
+    let server = Server::http(("127.0.0.1", port)).unwrap_or_else(|err| {
+        eprintln!("can't listen on port {port}: {err}");
+        process::exit(1);
+    });
+    eprintln!("serving {} levels on http://127.0.0.1:{port}/database", mock.levels().len());
+
+    for mut request in server.incoming_requests() {
+        let mut body = String::new();
+        let response = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => match mock.handle(request.url(), &body) {
+                Some(response) => Response::from_string(response),
+                None => Response::from_string(String::from("-1")).with_status_code(404)
+            },
+            Err(_) => Response::from_string(String::from("-1")).with_status_code(400)
+        };
+        if let Err(err) = request.respond(response) {
+            eprintln!("{err}");
+        }
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: gd-mock-server [--port <port>] [--songs <CCGameManager.dat>] <directory of .gmd files | CCLocalLevels.dat>");
+    process::exit(2);
+}
@@ -0,0 +1,136 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use serde::{Deserialize, Deserializer, de};
+use crate::{
+    icons::{GameMode, PlayerColor},
+    search::PageInfo,
+    serde::{
+        robtop::{self, Separators},
+        error::DeResult
+    }
+};
+
+/// Comment of getGJComments21 or getGJAccountComments20, whose keys are `~`
+/// separated
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Comment {
+    #[serde(rename = "6")]
+    pub id: i32,
+    /// Decoded text, sent as base64
+    #[serde(rename = "2")]
+    #[serde(default, deserialize_with = "deserialize_base64")]
+    pub content: String,
+    /// Missing from account comments
+    #[serde(rename = "3")]
+    #[serde(default)]
+    pub player_id: i32,
+    #[serde(rename = "4")]
+    #[serde(default)]
+    pub likes: i32,
+    #[serde(rename = "7")]
+    #[serde(default)]
+    pub is_spam: bool,
+    /// How long ago it was posted, e.g. `2 hours`
+    #[serde(rename = "9")]
+    #[serde(default)]
+    pub age: String,
+    /// Percentage the author got on the level, if they chose to show it
+    #[serde(rename = "10")]
+    #[serde(default)]
+    pub percent: Option<i32>,
+    /// 1 for moderators, 2 for elder moderators
+    #[serde(rename = "11")]
+    #[serde(default)]
+    pub mod_badge: i32,
+    /// `r,g,b` of the text of moderators
+    #[serde(rename = "12")]
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Only level comments have the author after a `:`
+    #[serde(skip)]
+    pub author: Option<CommentAuthor>
+}
+
+impl Comment {
+    /// Reads `comment` or `comment:author`
+    fn from_response(record: &str) -> DeResult<Self> {
+        let (comment, author) = match record.split_once(':') {
+            Some((comment, author)) => (comment, Some(author)),
+            None => (record, None)
+        };
+        let mut comment: Self = robtop::from_str_with(comment, Separators::COMMENT)?;
+        comment.author = author
+            .map(|author| robtop::from_str_with(author, Separators::COMMENT))
+            .transpose()?;
+        Ok(comment)
+    }
+}
+
+/// Author of a level comment, the icon fields mean the same as those of
+/// `icons::IconKit`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct CommentAuthor {
+    #[serde(rename = "1")]
+    pub name: String,
+    /// ID of the icon of `icon_type`
+    #[serde(rename = "9")]
+    pub icon: i32,
+    #[serde(rename = "10")]
+    pub color: PlayerColor,
+    #[serde(rename = "11")]
+    pub color_secondary: PlayerColor,
+    #[serde(rename = "14")]
+    pub icon_type: GameMode,
+    /// Sent as 0 or 2
+    #[serde(rename = "15")]
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    pub glow: bool,
+    #[serde(rename = "16")]
+    #[serde(default)]
+    pub account_id: i32
+}
+
+/// getGJComments21 or getGJAccountComments20 response, comments followed by
+/// the pagination
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommentPage {
+    pub comments: Vec<Comment>,
+    pub page: PageInfo
+}
+
+impl CommentPage {
+    /// The `-1` the server answers with when there are no comments is an
+    /// empty page
+    pub fn from_response(response: &str) -> DeResult<Self> {
+        let response = response.trim();
+        if response == "-1" {
+            return Ok(Self::default())
+        }
+        let mut sections = robtop::sections(response);
+        let comments = sections.next().unwrap_or_default()
+            .split(Separators::COMMENT.record)
+            .filter(|record| !record.is_empty())
+            .map(Comment::from_response)
+            .collect::<DeResult<_>>()?;
+        let page = match sections.next().unwrap_or_default() {
+            "" => PageInfo::default(),
+            page => PageInfo::from_response(page)?
+        };
+        Ok(Self { comments, page })
+    }
+}
+
+fn deserialize_base64<'de, D>(deserializer: D) -> Result<String, D::Error>
+where D: Deserializer<'de> {
+    let encoded = robtop::deserialize_string(deserializer)?;
+    URL_SAFE.decode(&encoded).ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .ok_or_else(|| de::Error::custom(format!("invalid base64 comment {encoded:?}")))
+}
+
+fn deserialize_flag<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where D: Deserializer<'de> {
+    i32::deserialize(deserializer).map(|flag| flag != 0)
+}
+
+#[cfg(test)]
+mod tests;
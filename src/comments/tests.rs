@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn parses_level_comments() {
+    let response = concat!(
+        "2~TmljZSBsZXZlbDogMTAvMTA=~3~3935672~4~12~7~0~10~87~9~2 hours~6~500~11~1~12~75,255,75",
+        ":1~Tester~9~35~10~3~11~12~14~0~15~2~16~161",
+        "|2~SGVsbG8gd29ybGQ=~3~71~4~-2~7~1~10~0~9~1 day~6~501",
+        ":1~RobTop~9~1~10~0~11~3~14~1~15~0~16~71",
+        "#2:0:10"
+    );
+    let page = CommentPage::from_response(response).unwrap();
+    let comment = &page.comments[0];
+    assert_eq!(comment.content, "Nice level: 10/10");
+    assert_eq!((comment.id, comment.player_id, comment.likes, comment.percent), (500, 3935672, 12, Some(87)));
+    assert_eq!(comment.color.as_deref(), Some("75,255,75"));
+    let author = comment.author.as_ref().unwrap();
+    assert_eq!((author.name.as_str(), author.icon, author.account_id), ("Tester", 35, 161));
+    assert_eq!((author.color, author.icon_type), (PlayerColor(3), GameMode::Cube));
+    assert!(author.glow);
+
+    assert!(page.comments[1].is_spam);
+    assert_eq!(page.comments[1].author.as_ref().map(|author| author.icon_type), Some(GameMode::Ship));
+    assert_eq!(page.page, PageInfo { total: 2, offset: 0, count: 10 });
+}
+
+#[test]
+fn parses_account_comments() {
+    let page = CommentPage::from_response("2~SGVsbG8gd29ybGQ=~4~3~9~5 minutes~6~77#1:0:10").unwrap();
+    assert_eq!(page.comments[0].content, "Hello world");
+    assert_eq!(page.comments[0].author, None);
+    assert_eq!(CommentPage::from_response("-1").unwrap(), CommentPage::default());
+}
+
+#[test]
+fn keeps_unknown_icon_types() {
+    let page = CommentPage::from_response("2~SGk=~4~0~9~1 day~6~502:1~Tester~9~35~10~3~11~12~14~15~15~0~16~161#1:0:10").unwrap();
+    assert_eq!(page.comments[0].author.as_ref().map(|author| author.icon_type), Some(GameMode::Other(15)));
+}
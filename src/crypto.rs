@@ -0,0 +1,99 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use sha1::{Digest, Sha1};
+
+/// Salt of the hashes of level downloads
+pub const LEVEL_SALT: &str = "xI25fpAapCQg";
+/// Key the level password is XORed with in responses
+pub const LEVEL_PASSWORD_KEY: &str = "26364";
+/// Key of the checks of level downloads and uploads
+pub const LEVEL_KEY: &str = "41274";
+/// Salt and key of the checks of likes
+pub const LIKE_SALT: &str = "ysg6pUrtjn0J";
+pub const LIKE_KEY: &str = "58281";
+/// Salt of `gjp2`
+pub const GJP2_SALT: &str = "mI29fmAnxgTs";
+
+/// Amount of level string characters the level hash is computed over
+const LEVEL_HASH_SAMPLES: usize = 40;
+/// Amount of level string characters the upload seed is made of
+const LEVEL_SEED_SAMPLES: usize = 50;
+
+/// Lowercase hex SHA-1 of the data followed by the salt
+pub fn salted_sha1(data: &str, salt: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(data);
+    hasher.update(salt);
+    format!("{:x}", hasher.finalize())
+}
+
+/// XORs the data with the key repeated over its whole length
+pub fn xor_cycle(data: &[u8], key: &[u8]) -> Vec<u8> {
+    data.iter().zip(key.iter().cycle()).map(|(byte, key)| byte ^ key).collect()
+}
+
+/// Reverses `xor_base64`, `None` if the data isn't valid base64 or UTF-8
+pub fn xor_base64_decode(encoded: &str, key: &str) -> Option<String> {
+    let decoded = URL_SAFE.decode(encoded).ok()?;
+    String::from_utf8(xor_cycle(&decoded, key.as_bytes())).ok()
+}
+
+/// XOR with a cyclic key then base64, how passwords and checks are sent
+pub fn xor_base64(data: &str, key: &str) -> String {
+    URL_SAFE.encode(xor_cycle(data.as_bytes(), key.as_bytes()))
+}
+
+/// `chk` of a request, the salted SHA-1 of the values XORed with the key
+pub fn chk(values: &[&str], salt: &str, key: &str) -> String {
+    xor_base64(&salted_sha1(&values.concat(), salt), key)
+}
+
+/// Password hash sent with requests made by an account
+pub fn gjp2(password: &str) -> String {
+    salted_sha1(password, GJP2_SALT)
+}
+
+/// `seed2` of a level upload, a check over 50 characters sampled evenly from
+/// the level string, or the whole string if it's shorter
+pub fn level_seed(level_string: &str) -> String {
+    let bytes = level_string.as_bytes();
+    let seed = if bytes.len() < LEVEL_SEED_SAMPLES {
+        level_string.to_string()
+    } else {
+        bytes.iter()
+            .step_by(bytes.len() / LEVEL_SEED_SAMPLES)
+            .take(LEVEL_SEED_SAMPLES)
+            .map(|byte| *byte as char)
+            .collect()
+    };
+    chk(&[&seed], LEVEL_SALT, LEVEL_KEY)
+}
+
+/// First hash of a level download, computed over 40 characters sampled
+/// evenly from the level string, or the whole string if it's shorter
+pub fn level_string_hash(level_string: &str) -> String {
+    let bytes = level_string.as_bytes();
+    if bytes.len() <= LEVEL_HASH_SAMPLES {
+        return salted_sha1(level_string, LEVEL_SALT)
+    }
+    let step = bytes.len() / LEVEL_HASH_SAMPLES;
+    let sampled: String = bytes.iter()
+        .step_by(step)
+        .take(LEVEL_HASH_SAMPLES)
+        .map(|byte| *byte as char)
+        .collect();
+    salted_sha1(&sampled, LEVEL_SALT)
+}
+
+/// Hash ending a getGJLevels21 or getGJMapPacks21 response, over the first
+/// and last digit of every ID followed by its stars and coins, which are
+/// 1 or 0 for whether the coins of a level are verified
+pub fn search_page_hash(items: &[(i32, i32, i32)]) -> String {
+    let data: String = items.iter()
+        .map(|(id, stars, coins)| {
+            let id = id.to_string();
+            let (first, last) = (&id[..1], &id[id.len() - 1..]);
+            format!("{first}{last}{stars}{coins}")
+        })
+        .collect();
+    salted_sha1(&data, LEVEL_SALT)
+}
@@ -0,0 +1,82 @@
+use std::{fmt, time::Duration};
+use crate::serde::error::{DeError, DeResult};
+
+/// Indices of weekly levels start at this
+const WEEKLY_OFFSET: i32 = 100_000;
+/// Indices of event levels start at this
+const EVENT_OFFSET: i32 = 200_000;
+
+/// Which timed level getGJDailyLevel is asked about, the `type` of the request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DailyKind {
+    Daily,
+    Weekly,
+    /// Added in 2.2
+    Event
+}
+
+impl DailyKind {
+    /// `type` field of getGJDailyLevel
+    pub fn id(self) -> i32 {
+        match self {
+            Self::Daily => 0,
+            Self::Weekly => 1,
+            Self::Event => 2
+        }
+    }
+
+    /// Level ID downloadGJLevel22 takes for the current level of this kind
+    pub fn level_id(self) -> i32 {
+        -1 - self.id()
+    }
+
+    /// Kind of the level with this index
+    pub fn from_index(index: i32) -> Self {
+        if index >= EVENT_OFFSET {
+            Self::Event
+        } else if index >= WEEKLY_OFFSET {
+            Self::Weekly
+        } else {
+            Self::Daily
+        }
+    }
+}
+
+/// getGJDailyLevel response, stored as `index|secondsLeft`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyInfo {
+    /// Increases with every new level, offset by 100000 for weekly levels
+    /// and 200000 for event levels
+    pub index: i32,
+    /// Until the next level replaces this one
+    pub time_left: Duration
+}
+
+impl DailyInfo {
+    pub fn from_response(response: &str) -> DeResult<Self> {
+        let (index, seconds_left) = response.trim().split_once('|').ok_or(DeError::Deserialization)?;
+        Ok(Self {
+            index: index.parse().map_err(|_| DeError::Deserialization)?,
+            time_left: Duration::from_secs(seconds_left.parse().map_err(|_| DeError::Deserialization)?)
+        })
+    }
+
+    pub fn kind(&self) -> DailyKind {
+        DailyKind::from_index(self.index)
+    }
+
+    /// Number shown in game, e.g. daily #2000 or weekly #300
+    pub fn number(&self) -> i32 {
+        self.index % WEEKLY_OFFSET
+    }
+}
+
+impl fmt::Display for DailyInfo {
+    /// The response `from_response` reads
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}|{}", self.index, self.time_left.as_secs())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,21 @@
+use super::*;
+
+#[test]
+fn parses_daily_info() {
+    let daily = DailyInfo::from_response("2150|43200").unwrap();
+    assert_eq!((daily.kind(), daily.number(), daily.time_left), (DailyKind::Daily, 2150, Duration::from_secs(43200)));
+    assert_eq!(daily.to_string(), "2150|43200");
+
+    let weekly = DailyInfo::from_response("100310|86400\n").unwrap();
+    assert_eq!((weekly.kind(), weekly.number()), (DailyKind::Weekly, 310));
+    assert_eq!(DailyInfo::from_response("200012|60").unwrap().kind(), DailyKind::Event);
+    assert!(DailyInfo::from_response("-1").is_err());
+}
+
+#[test]
+fn maps_kinds_to_request_ids() {
+    let ids: Vec<_> = [DailyKind::Daily, DailyKind::Weekly, DailyKind::Event].iter()
+        .map(|kind| (kind.id(), kind.level_id()))
+        .collect();
+    assert_eq!(ids, [(0, -1), (1, -2), (2, -3)]);
+}
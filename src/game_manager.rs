@@ -1,4 +1,14 @@
+use std::collections::{HashMap, BTreeMap};
 use serde::{Serialize, Deserialize};
+use crate::{
+    icons::IconKit,
+    unlocks::Unlocks,
+    interactions::{KeySet, LikedItem, LikeItemType},
+    songs::SongLibrary,
+    stats::CompletedLevel,
+    serde::Value,
+    options::{*, game_variables as gv}
+};
 
 // TODO: more fields
 
@@ -9,31 +19,63 @@ pub struct GameManagerDB {
     sfx_volume: f32,
     // #[serde(rename = "playerUUID")]
     // player_uuid: String,
+    /// Device ID the game generates on first launch
+    #[serde(rename = "playerUDID")]
+    #[serde(default)]
+    player_udid: String,
     player_name: String,
     #[serde(rename = "playerUserID")]
     player_user_id: i32,
-    player_frame: i32,
-    player_ship: i32,
-    player_ball: i32,
-    player_bird: i32,
-    player_dart: i32,
-    player_robot: i32,
-    player_spider: i32,
-    player_color: i32,
-    #[serde(rename = "playerColor2")]
-    player_color_secondary: i32,
-    player_streak: i32,
-    player_death_effect: i32,
-    player_icon_type: i32,
-    #[serde(default)]
-    player_glow: bool,
+    #[serde(rename = "GJA_001")]
+    #[serde(default)]
+    account_name: Option<String>,
+    #[serde(rename = "GJA_002")]
+    #[serde(default)]
+    account_password: Option<String>,
+    #[serde(rename = "GJA_003")]
+    #[serde(default)]
+    account_id: Option<i32>,
+    /// Stored instead of the password since 2.2
+    #[serde(rename = "GJA_005")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account_gjp2: Option<String>,
+    #[serde(flatten)]
+    icon_kit: IconKit,
     #[serde(default)]
     secret_number: i32,
     // hasRP: bool,
-    // valueKeeper,
-    // unlockValueKeeper,
+    #[serde(default)]
+    value_keeper: HashMap<String, String>,
+    #[serde(default)]
+    unlock_value_keeper: HashMap<String, String>,
     // customObjectDict,
     // reportedAchievements,
+    #[serde(rename = "GLM_06")]
+    #[serde(default)]
+    followed_accounts: KeySet<i32>,
+    #[serde(rename = "GLM_12")]
+    #[serde(default)]
+    liked_items: KeySet<LikedItem>,
+    #[serde(rename = "GLM_13")]
+    #[serde(default)]
+    rated_levels: KeySet<i32>,
+    #[serde(rename = "GLM_14")]
+    #[serde(default)]
+    reported_levels: KeySet<i32>,
+    #[serde(rename = "GLM_15")]
+    #[serde(default)]
+    rated_demons: KeySet<i32>,
+    /// Statistics by their ID, e.g. jumps is `"1"`
+    #[serde(rename = "GS_value")]
+    #[serde(default)]
+    stats: HashMap<String, String>,
+    /// Kept as plain keys since not every kind of completion is modeled
+    #[serde(rename = "GS_completed")]
+    #[serde(default)]
+    completed: KeySet<String>,
+    #[serde(rename = "MDLM_001")]
+    #[serde(default)]
+    song_library: SongLibrary,
     #[serde(default)]
     show_song_markers: bool,
     #[serde(default)]
@@ -53,5 +95,201 @@ pub struct GameManagerDB {
     has_rated_game: bool,
     binary_version: i32,
     resolution: i32,
-    tex_quality: i32
+    tex_quality: TextureQuality,
+    /// Keys not modeled above, kept so that saving doesn't drop them
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>
+}
+
+impl GameManagerDB {
+    pub fn player_name(&self) -> &str {
+        &self.player_name
+    }
+
+    /// Player ID the servers assigned to this device, sent as `uuid`
+    pub fn player_user_id(&self) -> i32 {
+        self.player_user_id
+    }
+
+    /// Device ID, sent as `udid`
+    pub fn player_udid(&self) -> &str {
+        &self.player_udid
+    }
+
+    /// Name of the logged in account
+    pub fn account_name(&self) -> Option<&str> {
+        self.account_name.as_deref()
+    }
+
+    /// Password of the logged in account, only stored before 2.2
+    pub fn account_password(&self) -> Option<&str> {
+        self.account_password.as_deref()
+    }
+
+    /// `crypto::gjp2` of the password of the logged in account, only stored
+    /// since 2.2
+    pub fn account_gjp2(&self) -> Option<&str> {
+        self.account_gjp2.as_deref()
+    }
+
+    pub fn account_id(&self) -> Option<i32> {
+        self.account_id.filter(|id| *id > 0)
+    }
+
+    pub fn icon_kit(&self) -> &IconKit {
+        &self.icon_kit
+    }
+
+    pub fn icon_kit_mut(&mut self) -> &mut IconKit {
+        &mut self.icon_kit
+    }
+
+    pub fn unlocks(&self) -> Unlocks<'_> {
+        Unlocks::new(&self.value_keeper, &self.unlock_value_keeper)
+    }
+
+    /// Raw statistics, see `stat`
+    pub fn stats(&self) -> &HashMap<String, String> {
+        &self.stats
+    }
+
+    /// Value of a statistic, 0 if it was never counted
+    pub fn stat(&self, id: i32) -> i64 {
+        self.stats.get(&id.to_string())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+
+    pub fn completed_levels(&self) -> impl Iterator<Item = CompletedLevel> + '_ {
+        self.completed.iter().filter_map(|key| key.parse().ok())
+    }
+
+    pub fn has_completed(&self, level: CompletedLevel) -> bool {
+        self.completed.contains(&level.to_string())
+    }
+
+    /// Custom songs the game has downloaded
+    pub fn song_library(&self) -> &SongLibrary {
+        &self.song_library
+    }
+
+    /// Account IDs of followed creators
+    pub fn followed_accounts(&self) -> &KeySet<i32> {
+        &self.followed_accounts
+    }
+
+    pub fn followed_accounts_mut(&mut self) -> &mut KeySet<i32> {
+        &mut self.followed_accounts
+    }
+
+    pub fn liked_items(&self) -> &KeySet<LikedItem> {
+        &self.liked_items
+    }
+
+    pub fn liked_items_mut(&mut self) -> &mut KeySet<LikedItem> {
+        &mut self.liked_items
+    }
+
+    /// IDs of levels the player sent a star rating for
+    pub fn rated_levels(&self) -> &KeySet<i32> {
+        &self.rated_levels
+    }
+
+    pub fn reported_levels(&self) -> &KeySet<i32> {
+        &self.reported_levels
+    }
+
+    /// IDs of levels the player sent a demon difficulty rating for
+    pub fn rated_demons(&self) -> &KeySet<i32> {
+        &self.rated_demons
+    }
+
+    pub fn is_following(&self, account_id: i32) -> bool {
+        self.followed_accounts.contains(&account_id)
+    }
+
+    fn has_voted(&self, item_type: LikeItemType, id: i32, is_like: bool) -> bool {
+        self.liked_items.iter().any(|item| {
+            item.item_type == item_type && item.id == id && item.is_like == is_like
+        })
+    }
+
+    pub fn has_liked_level(&self, level_id: i32) -> bool {
+        self.has_voted(LikeItemType::Level, level_id, true)
+    }
+
+    pub fn has_disliked_level(&self, level_id: i32) -> bool {
+        self.has_voted(LikeItemType::Level, level_id, false)
+    }
+
+    pub fn has_liked_comment(&self, comment_id: i32) -> bool {
+        self.has_voted(LikeItemType::Comment, comment_id, true)
+            || self.has_voted(LikeItemType::AccountComment, comment_id, true)
+    }
+
+    pub fn has_rated_level(&self, level_id: i32) -> bool {
+        self.rated_levels.contains(&level_id) || self.rated_demons.contains(&level_id)
+    }
+
+    pub fn has_reported_level(&self, level_id: i32) -> bool {
+        self.reported_levels.contains(&level_id)
+    }
+
+    fn game_variable(&self, key: &str) -> bool {
+        self.value_keeper.get(key).is_some_and(|value| value == "1")
+    }
+
+    fn set_game_variable(&mut self, key: &str, value: bool) {
+        self.value_keeper.insert(key.to_string(), String::from(if value { "1" } else { "0" }));
+    }
+
+    pub fn options(&self) -> Options {
+        Options {
+            audio: AudioOptions {
+                music_volume: self.bg_volume,
+                sfx_volume: self.sfx_volume
+            },
+            graphics: GraphicsOptions {
+                resolution: self.resolution,
+                texture_quality: self.tex_quality,
+                smooth_fix: self.game_variable(gv::SMOOTH_FIX)
+            },
+            gameplay: GameplayOptions {
+                auto_retry: self.game_variable(gv::AUTO_RETRY),
+                auto_checkpoints: self.game_variable(gv::AUTO_CHECKPOINTS),
+                fast_practice_reset: self.game_variable(gv::FAST_PRACTICE_RESET),
+                flip_two_player_controls: self.game_variable(gv::FLIP_TWO_PLAYER_CONTROLS),
+                show_cursor: self.game_variable(gv::SHOW_CURSOR),
+                show_percentage: self.game_variable(gv::SHOW_PERCENTAGE),
+                show_song_markers: self.show_song_markers,
+                show_progress_bar: self.show_progress_bar,
+                disable_thumbstick: self.game_variable(gv::DISABLE_THUMBSTICK)
+            },
+            editor: EditorOptions {
+                swipe: self.game_variable(gv::EDITOR_SWIPE),
+                increase_max_undo: self.game_variable(gv::EDITOR_INCREASE_MAX_UNDO)
+            }
+        }
+    }
+
+    /// Overwrites every setting covered by `Options`
+    pub fn set_options(&mut self, options: &Options) {
+        let Options { audio, graphics, gameplay, editor } = options;
+        self.bg_volume = audio.music_volume;
+        self.sfx_volume = audio.sfx_volume;
+        self.resolution = graphics.resolution;
+        self.tex_quality = graphics.texture_quality;
+        self.set_game_variable(gv::SMOOTH_FIX, graphics.smooth_fix);
+        self.set_game_variable(gv::AUTO_RETRY, gameplay.auto_retry);
+        self.set_game_variable(gv::AUTO_CHECKPOINTS, gameplay.auto_checkpoints);
+        self.set_game_variable(gv::FAST_PRACTICE_RESET, gameplay.fast_practice_reset);
+        self.set_game_variable(gv::FLIP_TWO_PLAYER_CONTROLS, gameplay.flip_two_player_controls);
+        self.set_game_variable(gv::SHOW_CURSOR, gameplay.show_cursor);
+        self.set_game_variable(gv::SHOW_PERCENTAGE, gameplay.show_percentage);
+        self.show_song_markers = gameplay.show_song_markers;
+        self.show_progress_bar = gameplay.show_progress_bar;
+        self.set_game_variable(gv::DISABLE_THUMBSTICK, gameplay.disable_thumbstick);
+        self.set_game_variable(gv::EDITOR_SWIPE, editor.swipe);
+        self.set_game_variable(gv::EDITOR_INCREASE_MAX_UNDO, editor.increase_max_undo);
+    }
 }
@@ -0,0 +1,192 @@
+use serde::{Serialize, Deserialize};
+
+/// Every icon the player has selected, stored under the `player*` keys of
+/// CCGameManager.dat
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IconKit {
+    #[serde(rename = "playerFrame")]
+    pub cube: i32,
+    #[serde(rename = "playerShip")]
+    pub ship: i32,
+    #[serde(rename = "playerBall")]
+    pub ball: i32,
+    #[serde(rename = "playerBird")]
+    pub ufo: i32,
+    #[serde(rename = "playerDart")]
+    pub wave: i32,
+    #[serde(rename = "playerRobot")]
+    pub robot: i32,
+    #[serde(rename = "playerSpider")]
+    pub spider: i32,
+    /// Added in 2.2
+    #[serde(rename = "playerSwing")]
+    #[serde(default)]
+    pub swing: i32,
+    /// Added in 2.2
+    #[serde(rename = "playerJetpack")]
+    #[serde(default)]
+    pub jetpack: i32,
+    #[serde(rename = "playerStreak")]
+    pub trail: i32,
+    #[serde(rename = "playerDeathEffect")]
+    pub death_effect: i32,
+    #[serde(rename = "playerColor")]
+    pub color: PlayerColor,
+    #[serde(rename = "playerColor2")]
+    pub color_secondary: PlayerColor,
+    #[serde(rename = "playerIconType")]
+    pub icon_type: GameMode,
+    #[serde(rename = "playerGlow")]
+    #[serde(default)]
+    pub glow: bool
+}
+
+impl IconKit {
+    /// Icon ID shown for the given game mode, the cube for unknown modes
+    pub fn icon(&self, mode: GameMode) -> i32 {
+        match mode {
+            GameMode::Cube | GameMode::Other(_) => self.cube,
+            GameMode::Ship => self.ship,
+            GameMode::Ball => self.ball,
+            GameMode::Ufo => self.ufo,
+            GameMode::Wave => self.wave,
+            GameMode::Robot => self.robot,
+            GameMode::Spider => self.spider,
+            GameMode::Swing => self.swing,
+            GameMode::Jetpack => self.jetpack
+        }
+    }
+
+    /// Icon ID of the icon type shown on the player's profile
+    pub fn main_icon(&self) -> i32 {
+        self.icon(self.icon_type)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(from = "i32", into = "i32")]
+pub enum GameMode {
+    Cube,
+    Ship,
+    Ball,
+    Ufo,
+    Wave,
+    Robot,
+    Spider,
+    /// Added in 2.2
+    Swing,
+    /// Added in 2.2, only used in the editor and in platformer levels
+    Jetpack,
+    /// Mode this version doesn't know, kept so that it's written back as is
+    Other(i32)
+}
+
+impl From<i32> for GameMode {
+    fn from(value: i32) -> Self {
+        match value {
+            0 => Self::Cube,
+            1 => Self::Ship,
+            2 => Self::Ball,
+            3 => Self::Ufo,
+            4 => Self::Wave,
+            5 => Self::Robot,
+            6 => Self::Spider,
+            7 => Self::Swing,
+            8 => Self::Jetpack,
+            _ => Self::Other(value)
+        }
+    }
+}
+
+impl From<GameMode> for i32 {
+    fn from(mode: GameMode) -> Self {
+        match mode {
+            GameMode::Cube => 0,
+            GameMode::Ship => 1,
+            GameMode::Ball => 2,
+            GameMode::Ufo => 3,
+            GameMode::Wave => 4,
+            GameMode::Robot => 5,
+            GameMode::Spider => 6,
+            GameMode::Swing => 7,
+            GameMode::Jetpack => 8,
+            GameMode::Other(value) => value
+        }
+    }
+}
+
+/// Index into the game's fixed color palette
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(from = "i32", into = "i32")]
+pub struct PlayerColor(pub i32);
+
+const PALETTE: [(u8, u8, u8); 42] = [
+    (125, 255, 0),
+    (0, 255, 0),
+    (0, 255, 125),
+    (0, 255, 255),
+    (0, 125, 255),
+    (0, 0, 255),
+    (125, 0, 255),
+    (255, 0, 255),
+    (255, 0, 125),
+    (255, 0, 0),
+    (255, 125, 0),
+    (255, 255, 0),
+    (255, 255, 255),
+    (185, 0, 255),
+    (255, 185, 0),
+    (0, 0, 0),
+    (0, 200, 255),
+    (175, 175, 175),
+    (90, 90, 90),
+    (255, 125, 125),
+    (0, 175, 75),
+    (0, 125, 125),
+    (0, 75, 175),
+    (75, 0, 175),
+    (125, 0, 125),
+    (175, 0, 75),
+    (175, 75, 0),
+    (125, 125, 0),
+    (75, 175, 0),
+    (255, 75, 0),
+    (150, 50, 0),
+    (150, 100, 0),
+    (100, 150, 0),
+    (0, 150, 100),
+    (0, 100, 150),
+    (100, 0, 150),
+    (150, 0, 100),
+    (150, 0, 0),
+    (0, 150, 0),
+    (0, 0, 150),
+    (125, 255, 175),
+    (125, 125, 255)
+];
+
+impl PlayerColor {
+    /// RGB value of the color, `None` if the index is outside of the palette.
+    /// Only the 42 colors of 2.1 are known, so this is also `None` for the
+    /// colors added in 2.2
+    pub fn rgb(self) -> Option<(u8, u8, u8)> {
+        usize::try_from(self.0).ok()
+            .and_then(|index| PALETTE.get(index))
+            .copied()
+    }
+}
+
+impl From<i32> for PlayerColor {
+    fn from(index: i32) -> Self {
+        Self(index)
+    }
+}
+
+impl From<PlayerColor> for i32 {
+    fn from(color: PlayerColor) -> Self {
+        color.0
+    }
+}
+
+#[cfg(test)]
+mod tests;
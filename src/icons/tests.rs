@@ -0,0 +1,71 @@
+use super::*;
+use crate::{
+    game_manager::GameManagerDB,
+    serde::{DataWithHeader, Value, from_any_reader, ser::to_xml}
+};
+
+const GAME_MANAGER: &str = concat!(
+    r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+    "<k>bgVolume</k><r>0.5</r><k>sfxVolume</k><r>1</r><k>playerName</k><s>Tester</s>",
+    "<k>playerUserID</k><i>77</i><k>playerFrame</k><i>12</i><k>playerShip</k><i>3</i>",
+    "<k>playerBall</k><i>4</i><k>playerBird</k><i>5</i><k>playerDart</k><i>6</i>",
+    "<k>playerRobot</k><i>7</i><k>playerSpider</k><i>8</i><k>playerSwing</k><i>10</i>",
+    "<k>playerJetpack</k><i>2</i><k>playerColor</k><i>9</i><k>playerColor2</k><i>12</i>",
+    "<k>playerStreak</k><i>2</i><k>playerDeathEffect</k><i>1</i><k>playerGlow</k><t/>",
+    "<k>playerIconType</k><i>7</i><k>bootups</k><i>3</i><k>binaryVersion</k><i>42</i>",
+    "<k>resolution</k><i>-1</i><k>texQuality</k><i>3</i><k>unmodeled</k><s>kept</s>",
+    "</dict></plist>"
+);
+
+#[test]
+fn looks_up_palette_colors() {
+    assert_eq!(PlayerColor(0).rgb(), Some((125, 255, 0)));
+    assert_eq!(PlayerColor(12).rgb(), Some((255, 255, 255)));
+    assert_eq!(PlayerColor(41).rgb(), Some((125, 125, 255)));
+    // added in 2.2
+    assert_eq!(PlayerColor(42).rgb(), None);
+    assert_eq!(PlayerColor(106).rgb(), None);
+    assert_eq!(PlayerColor(-1).rgb(), None);
+}
+
+#[test]
+fn converts_game_modes() {
+    for value in 0..10 {
+        assert_eq!(i32::from(GameMode::from(value)), value);
+    }
+    assert_eq!(GameMode::from(9), GameMode::Other(9));
+}
+
+#[test]
+fn keeps_unknown_icon_types() {
+    let xml = GAME_MANAGER.replace("<k>playerIconType</k><i>7</i>", "<k>playerIconType</k><i>12</i>");
+    let game_manager: GameManagerDB = from_any_reader(xml.as_bytes()).unwrap().t;
+    let icons = game_manager.icon_kit();
+    assert_eq!((icons.icon_type, icons.main_icon()), (GameMode::Other(12), 12));
+
+    let written: Value = from_any_reader(&to_xml(&DataWithHeader::new(game_manager)).unwrap()[..]).unwrap().t;
+    assert_eq!(written.get("playerIconType"), Some(&Value::Integer(12)));
+}
+
+#[test]
+fn reads_icons_of_2_2_saves() {
+    let game_manager: GameManagerDB = from_any_reader(GAME_MANAGER.as_bytes()).unwrap().t;
+    let icons = game_manager.icon_kit();
+    assert_eq!(icons.icon_type, GameMode::Swing);
+    assert_eq!((icons.main_icon(), icons.icon(GameMode::Jetpack)), (10, 2));
+    assert!(icons.glow);
+}
+
+#[test]
+fn round_trips_game_manager() {
+    let game_manager: GameManagerDB = from_any_reader(GAME_MANAGER.as_bytes()).unwrap().t;
+    let xml = to_xml(&DataWithHeader::new(game_manager)).unwrap();
+    let reread: GameManagerDB = from_any_reader(xml.as_slice()).unwrap().t;
+    assert_eq!(to_xml(&DataWithHeader::new(reread)).unwrap(), xml);
+
+    let original: Value = from_any_reader(GAME_MANAGER.as_bytes()).unwrap().t;
+    let written: Value = from_any_reader(xml.as_slice()).unwrap().t;
+    for (key, value) in original.as_dict().unwrap() {
+        assert_eq!(written.get(key), Some(value), "{key}");
+    }
+}
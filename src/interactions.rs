@@ -0,0 +1,149 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    hash::Hash,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    str::FromStr
+};
+use serde::{de, ser::SerializeMap, Serialize, Deserialize};
+
+/// Dict of the save whose keys are the only meaningful part. Keys that
+/// don't parse as `T` are kept as they are so that saving doesn't drop them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySet<T: Eq + Hash>(HashSet<T>, HashSet<String>);
+
+impl<T: Eq + Hash> KeySet<T> {
+    /// Keys this version can't parse, like those of newer game versions
+    pub fn unrecognized(&self) -> &HashSet<String> {
+        &self.1
+    }
+}
+
+impl<T: Eq + Hash> Default for KeySet<T> {
+    fn default() -> Self {
+        Self(HashSet::new(), HashSet::new())
+    }
+}
+
+impl<T: Eq + Hash> Deref for KeySet<T> {
+    type Target = HashSet<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Eq + Hash> DerefMut for KeySet<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Eq + Hash + Display> Serialize for KeySet<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len() + self.1.len()))?;
+        for key in &self.0 {
+            map.serialize_entry(&key.to_string(), "1")?;
+        }
+        for key in &self.1 {
+            map.serialize_entry(key, "1")?;
+        }
+        map.end()
+    }
+}
+
+struct KeySetVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Eq + Hash + FromStr> de::Visitor<'de> for KeySetVisitor<T> {
+    type Value = KeySet<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a dict")
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut set = KeySet::default();
+        while let Some(key) = map.next_key::<String>()? {
+            map.next_value::<de::IgnoredAny>()?;
+            match key.parse() {
+                Ok(parsed) => { set.0.insert(parsed); }
+                Err(_) => { set.1.insert(key); }
+            }
+        }
+        Ok(set)
+    }
+}
+
+impl<'de, T: Eq + Hash + FromStr> Deserialize<'de> for KeySet<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(KeySetVisitor(PhantomData))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LikeItemType {
+    Level,
+    Comment,
+    AccountComment,
+    /// Added in 2.2
+    List
+}
+
+impl LikeItemType {
+    /// Number of the type in like keys and requests
+    pub fn id(self) -> i32 {
+        match self {
+            Self::Level => 1,
+            Self::Comment => 2,
+            Self::AccountComment => 3,
+            Self::List => 4
+        }
+    }
+}
+
+/// Like or dislike left by the player, stored as
+/// `like_<type>_<id>_<is like>_<special>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LikedItem {
+    pub item_type: LikeItemType,
+    pub id: i32,
+    pub is_like: bool,
+    /// Level ID for level comments, 0 otherwise
+    pub special: i32
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidLikeKey;
+
+impl FromStr for LikedItem {
+    type Err = InvalidLikeKey;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.strip_prefix("like_").ok_or(InvalidLikeKey)?.split('_');
+        let mut next = || -> Result<i32, InvalidLikeKey> {
+            parts.next().and_then(|part| part.parse().ok()).ok_or(InvalidLikeKey)
+        };
+        let item_type = match next()? {
+            1 => LikeItemType::Level,
+            2 => LikeItemType::Comment,
+            3 => LikeItemType::AccountComment,
+            4 => LikeItemType::List,
+            _ => return Err(InvalidLikeKey)
+        };
+        let id = next()?;
+        let is_like = next()? != 0;
+        let special = next()?;
+        if parts.next().is_some() { return Err(InvalidLikeKey) }
+        Ok(Self { item_type, id, is_like, special })
+    }
+}
+
+impl Display for LikedItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "like_{}_{}_{}_{}", self.item_type.id(), self.id, self.is_like as i32, self.special)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,88 @@
+use super::*;
+use crate::{
+    game_manager::GameManagerDB,
+    serde::{DataWithHeader, from_any_reader, ser::to_xml}
+};
+
+/// `GLM_12` of CCGameManager.dat
+const LIKES: &str = concat!(
+    r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+    "<k>like_1_128_1_0</k><s>1</s><k>like_1_4284013_0_0</k><s>1</s>",
+    "<k>like_2_5001_1_128</k><s>1</s><k>like_3_77_1_0</k><s>1</s>",
+    "<k>like_4_128_1_0</k><s>1</s>",
+    "</dict></plist>"
+);
+
+#[test]
+fn parses_like_keys() {
+    let likes: KeySet<LikedItem> = from_any_reader(LIKES.as_bytes()).unwrap().t;
+    assert_eq!(likes.len(), 5);
+    assert!(likes.contains(&LikedItem { item_type: LikeItemType::Level, id: 4284013, is_like: false, special: 0 }));
+    assert!(likes.contains(&LikedItem { item_type: LikeItemType::Comment, id: 5001, is_like: true, special: 128 }));
+    assert!(likes.contains(&LikedItem { item_type: LikeItemType::AccountComment, id: 77, is_like: true, special: 0 }));
+    assert!(likes.contains(&LikedItem { item_type: LikeItemType::List, id: 128, is_like: true, special: 0 }));
+}
+
+#[test]
+fn formats_like_keys() {
+    for key in ["like_1_128_1_0", "like_2_5001_0_128", "like_3_77_1_0", "like_4_128_1_0"] {
+        assert_eq!(key.parse::<LikedItem>().unwrap().to_string(), key);
+    }
+}
+
+#[test]
+fn keeps_unrecognized_like_keys() {
+    for key in ["", "like_", "dislike_1_128_1_0", "like_5_128_1_0", "like_1_abc_1_0", "like_1_128_1", "like_1_128_1_0_5"] {
+        assert_eq!(key.parse::<LikedItem>(), Err(InvalidLikeKey), "{key}");
+    }
+    let unknown = LIKES.replace("like_3_77_1_0", "like_9_77_1_0");
+    let likes: KeySet<LikedItem> = from_any_reader(unknown.as_bytes()).unwrap().t;
+    assert_eq!(likes.len(), 4);
+    assert!(likes.unrecognized().contains("like_9_77_1_0"));
+
+    let xml = String::from_utf8(to_xml(&DataWithHeader::new(likes)).unwrap()).unwrap();
+    assert!(xml.contains("<k>like_9_77_1_0</k><s>1</s>"));
+}
+
+#[test]
+fn round_trips_key_sets() {
+    let likes: KeySet<LikedItem> = from_any_reader(LIKES.as_bytes()).unwrap().t;
+    let xml = to_xml(&DataWithHeader::new(likes.clone())).unwrap();
+    assert_eq!(from_any_reader::<KeySet<LikedItem>, _>(xml.as_slice()).unwrap().t, likes);
+
+    let mut accounts = KeySet::<i32>::default();
+    accounts.extend([71, 161]);
+    let xml = to_xml(&DataWithHeader::new(accounts.clone())).unwrap();
+    assert!(String::from_utf8(xml.clone()).unwrap().contains("<k>71</k><s>1</s>"));
+    assert_eq!(from_any_reader::<KeySet<i32>, _>(xml.as_slice()).unwrap().t, accounts);
+}
+
+/// CCGameManager.dat with every GLM set the game keeps for levels
+const GAME_MANAGER: &str = concat!(
+    r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+    "<k>bgVolume</k><r>0.5</r><k>sfxVolume</k><r>1</r><k>playerName</k><s>Tester</s>",
+    "<k>playerUserID</k><i>77</i><k>playerFrame</k><i>12</i><k>playerShip</k><i>3</i>",
+    "<k>playerBall</k><i>4</i><k>playerBird</k><i>5</i><k>playerDart</k><i>6</i>",
+    "<k>playerRobot</k><i>7</i><k>playerSpider</k><i>8</i><k>playerColor</k><i>9</i>",
+    "<k>playerColor2</k><i>12</i><k>playerStreak</k><i>2</i><k>playerDeathEffect</k><i>1</i>",
+    "<k>playerIconType</k><i>1</i><k>bootups</k><i>3</i><k>binaryVersion</k><i>42</i>",
+    "<k>resolution</k><i>-1</i><k>texQuality</k><i>3</i>",
+    "<k>GLM_06</k><d><k>71</k><s>1</s></d>",
+    "<k>GLM_12</k><d><k>like_1_128_1_0</k><s>1</s></d>",
+    "<k>GLM_13</k><d><k>1300</k><s>1</s></d>",
+    "<k>GLM_14</k><d><k>1400</k><s>1</s></d>",
+    "<k>GLM_15</k><d><k>1500</k><s>1</s></d>",
+    "<k>GLM_16</k><d><k>1600</k><s>1</s></d>",
+    "</dict></plist>"
+);
+
+#[test]
+fn reads_level_sets_from_their_glm_keys() {
+    let game_manager: GameManagerDB = from_any_reader(GAME_MANAGER.as_bytes()).unwrap().t;
+    assert!(game_manager.is_following(71));
+    assert!(game_manager.has_liked_level(128));
+    assert_eq!(game_manager.rated_levels().iter().collect::<Vec<_>>(), [&1300]);
+    assert_eq!(game_manager.reported_levels().iter().collect::<Vec<_>>(), [&1400]);
+    assert_eq!(game_manager.rated_demons().iter().collect::<Vec<_>>(), [&1500]);
+    assert!(!game_manager.has_rated_level(1600));
+}
@@ -1,6 +1,24 @@
-// TODO: organize game_manager and local_levels to make it easy to use
-
 #[allow(unused_variables)] // temporary
 pub mod serde;
 pub mod game_manager;
 pub mod local_levels;
+pub mod icons;
+pub mod unlocks;
+pub mod options;
+pub mod interactions;
+pub mod songs;
+pub mod save_location;
+pub mod save_writer;
+pub mod save_profile;
+pub mod stats;
+pub mod save_watcher;
+pub mod account_backup;
+pub mod crypto;
+pub mod search;
+pub mod requests;
+pub mod comments;
+pub mod users;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
+pub mod packs;
+pub mod daily;
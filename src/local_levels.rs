@@ -1,22 +1,420 @@
+use std::{collections::BTreeMap, fmt};
+use base64::{Engine, engine::general_purpose::URL_SAFE};
 use serde::{Serialize, Deserialize};
+use crate::{
+    crypto,
+    requests::{self, Account, LevelUpload, Request},
+    serde::{Value, value::from_value, robtop, error::{DeError, DeResult}}
+};
+
+pub mod merge;
+pub use merge::{merge, MergeResult, MergeConflict};
+pub mod lists;
+pub use lists::{LevelList, ListPage};
 
 // TODO: more fields
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct LocalLevelsDB {
     #[serde(rename = "LLM_01")]
     local_levels: Vec<Level>,
     #[serde(rename = "LLM_02")]
-    binary_version: i32
+    binary_version: i32,
+    /// Missing from saves older than 2.2
+    #[serde(rename = "LLM_03")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    lists: Vec<LevelList>,
+    /// Keys not modeled above, kept so that saving doesn't drop them
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>
+}
+
+impl LocalLevelsDB {
+    pub fn levels(&self) -> &[Level] {
+        &self.local_levels
+    }
+
+    pub fn levels_mut(&mut self) -> &mut Vec<Level> {
+        &mut self.local_levels
+    }
+
+    pub fn lists(&self) -> &[LevelList] {
+        &self.lists
+    }
+
+    pub fn lists_mut(&mut self) -> &mut Vec<LevelList> {
+        &mut self.lists
+    }
+
+    /// Levels of a recovered CCLocalLevels.dat that are complete enough to
+    /// deserialize, see `serde::recovery`
+    pub fn salvage_levels(recovered: &Value) -> Vec<Level> {
+        recovered.get("LLM_01")
+            .and_then(Value::as_array)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|level| from_value(level.clone()).ok())
+            .collect()
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Save keys of the response fields a save also stores, see
+/// `Level::use_save_keys`
+const RESPONSE_KEYS: [(&str, &str); 10] = [
+    ("3", "k3"),
+    ("5", "k16"),
+    ("10", "k11"),
+    ("12", "k8"),
+    ("15", "k23"),
+    ("31", "k43"),
+    ("37", "k64"),
+    ("39", "k66"),
+    ("40", "k72"),
+    ("45", "k48")
+];
+
+/// Level of CCLocalLevels.dat, also deserializable from server responses
+/// with `serde::robtop`, whose keys are listed as aliases
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Level {
-    #[serde(rename = "k1")]
+    #[serde(rename = "k1", alias = "1")]
     #[serde(default)]
     id: Option<i32>,
-    #[serde(rename = "k2")]
+    #[serde(rename = "k2", alias = "2")]
+    #[serde(deserialize_with = "robtop::deserialize_string")]
     name: String,
+    /// Encoded level data, missing for levels that were never opened
+    #[serde(rename = "k4", alias = "4")]
+    #[serde(default, deserialize_with = "robtop::deserialize_optional_string")]
+    level_string: Option<String>,
+    /// Responses only contain the player ID of the creator
     #[serde(rename = "k5")]
-    creator: String
+    #[serde(default, deserialize_with = "robtop::deserialize_string")]
+    creator: String,
+    #[serde(rename = "k6", alias = "6")]
+    #[serde(default)]
+    player_id: Option<i32>,
+    #[serde(rename = "k45", alias = "35")]
+    #[serde(default)]
+    custom_song_id: Option<i32>,
+    /// ID of the online level this one was copied from
+    #[serde(rename = "k42", alias = "30")]
+    #[serde(default)]
+    original_id: Option<i32>,
+    /// Keys not modeled above, kept so that saving doesn't drop them
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>
 }
+
+impl Level {
+    /// Reads a downloadGJLevel22 response, the level followed by `#`
+    /// separated hashes of its level string and of its rating fields
+    pub fn from_download_response(response: &str) -> DeResult<Self> {
+        let mut sections = robtop::sections(response);
+        let mut level: Self = robtop::from_str(sections.next().unwrap_or_default())?;
+        let (Some(level_hash), Some(rating)) = (sections.next(), sections.next()) else {
+            return Err(DeError::Custom(String::from("response has no hashes")))
+        };
+
+        let expected = crypto::level_string_hash(level.level_string().unwrap_or_default());
+        if level_hash != expected {
+            return Err(DeError::HashMismatch { expected, found: level_hash.to_string() })
+        }
+        let expected = rating_hash(|key| level.response_field(key));
+        if rating != expected {
+            return Err(DeError::HashMismatch { expected, found: rating.to_string() })
+        }
+        level.use_save_keys();
+        Ok(level)
+    }
+
+    /// Moves the fields of a server response to the keys a save stores them
+    /// under and drops the ones that only describe the level on the servers,
+    /// like its rating, so that it can be added to a `LocalLevelsDB`
+    pub(crate) fn use_save_keys(&mut self) {
+        let (response, other): (BTreeMap<_, _>, _) = std::mem::take(&mut self.other).into_iter()
+            .partition(|(key, _)| key.bytes().all(|byte| byte.is_ascii_digit()));
+        self.other = other;
+        for (key, save_key) in RESPONSE_KEYS {
+            let Some(value) = response.get(key) else { continue };
+            let value = match (save_key, value) {
+                ("k43" | "k72", Value::Integer(flag)) => Value::Bool(*flag != 0),
+                _ => value.clone()
+            };
+            self.other.insert(save_key.to_string(), value);
+        }
+        let password = match response.get("27") {
+            Some(Value::String(encoded)) => crypto::xor_base64_decode(encoded, crypto::LEVEL_PASSWORD_KEY),
+            Some(Value::Integer(encoded)) => crypto::xor_base64_decode(&encoded.to_string(), crypto::LEVEL_PASSWORD_KEY),
+            _ => None
+        };
+        let password = password.and_then(|password| password.parse().ok());
+        if let Some(password) = password {
+            self.other.insert(String::from("k41"), Value::Integer(save_password(password).into()));
+        }
+    }
+
+    /// Fields of this level in a server response, in the order the servers
+    /// send them. Rating fields are 0 since local levels aren't rated, and the
+    /// level data and password are only part of downloads
+    fn response_fields(&self, is_download: bool) -> Vec<(&'static str, String)> {
+        let upload = self.to_upload();
+        let mut fields = vec![
+            ("1", upload.level_id.to_string()),
+            ("2", upload.name.clone()),
+            ("3", URL_SAFE.encode(&upload.description))
+        ];
+        if is_download {
+            fields.push(("4", upload.level_string.clone()));
+        }
+        fields.extend([
+            ("5", upload.version.to_string()),
+            ("6", self.player_id.unwrap_or_default().to_string()),
+            ("8", String::from("10")),
+            ("9", String::from("0")),
+            ("10", self.int_field("k11").to_string()),
+            ("12", upload.audio_track.to_string()),
+            ("13", requests::GAME_VERSION.to_string()),
+            ("14", String::from("0")),
+            ("17", String::from("0")),
+            ("25", String::from("0")),
+            ("18", String::from("0")),
+            ("19", String::from("0")),
+            ("42", String::from("0")),
+            ("45", upload.objects.to_string()),
+            ("15", upload.length.to_string()),
+            ("30", upload.original_id.to_string()),
+            ("31", (upload.two_player as i32).to_string()),
+            ("35", upload.song_id.to_string()),
+            ("37", upload.coins.to_string()),
+            ("38", String::from("0")),
+            ("39", upload.requested_stars.to_string()),
+            ("40", (upload.ldm as i32).to_string())
+        ]);
+        if is_download {
+            let password = crypto::xor_base64(&upload.password.to_string(), crypto::LEVEL_PASSWORD_KEY);
+            fields.push(("27", password));
+        }
+        fields
+    }
+
+    /// Level as getGJLevels21 lists it, without its data
+    pub fn to_search_response(&self) -> String {
+        join_response_fields(&self.response_fields(false))
+    }
+
+    /// downloadGJLevel22 response of this level, what
+    /// `from_download_response` reads
+    pub fn to_download_response(&self) -> String {
+        let fields = self.response_fields(true);
+        let field = |key: &str| fields.iter()
+            .find(|(field, _)| *field == key)
+            .map_or_else(|| String::from("0"), |(_, value)| value.clone());
+        format!(
+            "{}#{}#{}",
+            join_response_fields(&fields),
+            crypto::level_string_hash(&field("4")),
+            rating_hash(field)
+        )
+    }
+
+    /// Field of a server response, "0" if missing
+    fn response_field(&self, key: &str) -> String {
+        match (key, self.other.get(key)) {
+            ("1", _) => self.id.unwrap_or_default().to_string(),
+            ("6", _) => self.player_id.unwrap_or_default().to_string(),
+            (_, Some(Value::String(text))) => text.clone(),
+            (_, Some(value)) => value.to_string(),
+            (_, None) => String::from("0")
+        }
+    }
+
+    /// Save field not modeled by `Level` as a number, 0 if missing
+    fn int_field(&self, key: &str) -> i32 {
+        match self.other.get(key) {
+            Some(Value::Integer(value)) => *value as i32,
+            Some(Value::Bool(value)) => *value as i32,
+            Some(Value::String(text)) => text.parse().unwrap_or_default(),
+            _ => 0
+        }
+    }
+
+    pub fn id(&self) -> Option<i32> {
+        self.id
+    }
+
+    pub fn set_id(&mut self, id: i32) {
+        self.id = Some(id);
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn creator(&self) -> &str {
+        &self.creator
+    }
+
+    pub fn set_creator(&mut self, creator: String) {
+        self.creator = creator;
+    }
+
+    /// Player ID of the creator, which unlike the account ID every player has
+    pub fn player_id(&self) -> Option<i32> {
+        self.player_id.filter(|id| *id > 0)
+    }
+
+    pub fn set_player_id(&mut self, player_id: i32) {
+        self.player_id = Some(player_id);
+    }
+
+    pub fn level_string(&self) -> Option<&str> {
+        self.level_string.as_deref()
+    }
+
+    pub fn set_level_string(&mut self, level_string: String) {
+        self.level_string = Some(level_string);
+    }
+
+    /// ID of the custom song, `None` if the level uses an official song
+    pub fn custom_song_id(&self) -> Option<i32> {
+        self.custom_song_id.filter(|id| *id > 0)
+    }
+
+    /// ID of the online level this one was copied from, `None` for
+    /// levels made from scratch
+    pub fn original_id(&self) -> Option<i32> {
+        self.original_id.filter(|id| *id > 0)
+    }
+
+    /// Copy password as uploaded, 0 for no copying, 1 for free copies and
+    /// the 6 digit passcode prefixed with 1 otherwise
+    fn upload_password(&self) -> i32 {
+        match self.int_field("k41") {
+            password @ (0 | 1) => password,
+            passcode => 1_000_000 + passcode
+        }
+    }
+
+    /// Fields of an upload of this level, saves store the description as
+    /// base64 while uploads take it plain
+    pub fn to_upload(&self) -> LevelUpload {
+        let description = match self.other.get("k3") {
+            Some(Value::String(encoded)) => URL_SAFE.decode(encoded).ok()
+                .and_then(|decoded| String::from_utf8(decoded).ok())
+                .unwrap_or_else(|| encoded.clone()),
+            _ => String::new()
+        };
+        LevelUpload {
+            level_id: self.id.unwrap_or_default(),
+            name: self.name.clone(),
+            description,
+            version: self.int_field("k16").max(1),
+            length: self.int_field("k23"),
+            audio_track: self.int_field("k8"),
+            song_id: self.custom_song_id().unwrap_or_default(),
+            objects: self.int_field("k48"),
+            coins: self.int_field("k64"),
+            requested_stars: self.int_field("k66"),
+            unlisted: self.int_field("k79") != 0,
+            ldm: self.int_field("k72") != 0,
+            password: self.upload_password(),
+            original_id: self.original_id().unwrap_or_default(),
+            two_player: self.int_field("k43") != 0,
+            level_string: self.level_string.clone().unwrap_or_default()
+        }
+    }
+
+    /// Level as a save stores it after the upload is received, the inverse of
+    /// `to_upload`
+    pub fn from_upload(upload: &LevelUpload, creator: &str, player_id: i32) -> Self {
+        let password = save_password(upload.password);
+        let other = [
+            ("k3", Value::String(URL_SAFE.encode(&upload.description))),
+            ("k16", Value::Integer(upload.version.into())),
+            ("k23", Value::Integer(upload.length.into())),
+            ("k8", Value::Integer(upload.audio_track.into())),
+            ("k48", Value::Integer(upload.objects.into())),
+            ("k64", Value::Integer(upload.coins.into())),
+            ("k66", Value::Integer(upload.requested_stars.into())),
+            ("k41", Value::Integer(password.into())),
+            ("k79", Value::Bool(upload.unlisted)),
+            ("k72", Value::Bool(upload.ldm)),
+            ("k43", Value::Bool(upload.two_player))
+        ];
+        Self {
+            id: Some(upload.level_id).filter(|id| *id > 0),
+            name: upload.name.clone(),
+            level_string: Some(upload.level_string.clone()),
+            creator: creator.to_string(),
+            player_id: Some(player_id),
+            custom_song_id: Some(upload.song_id),
+            original_id: Some(upload.original_id),
+            other: other.into_iter().map(|(key, value)| (key.to_string(), value)).collect()
+        }
+    }
+
+    /// uploadGJLevel21 request of this level, the seed is derived from the
+    /// level string so the same level always gives the same body
+    pub fn to_upload_request(&self, account: &Account) -> Request {
+        let upload = self.to_upload();
+        let seed = crypto::salted_sha1(&upload.level_string, "");
+        requests::upload_level(account, &upload, &seed[..10])
+    }
+
+    /// What identifies the same level across two saves, the level ID can't
+    /// be used since it only gets assigned on upload
+    pub fn identity(&self) -> LevelIdentity {
+        LevelIdentity {
+            name: self.name.clone(),
+            creator: self.creator.clone(),
+            original_id: self.original_id()
+        }
+    }
+}
+
+/// Copy password as saves store it, the inverse of `Level::upload_password`
+fn save_password(upload_password: i32) -> i32 {
+    match upload_password {
+        password @ (0 | 1) => password,
+        password => password - 1_000_000
+    }
+}
+
+/// Second hash of a level download, over
+/// `playerID,stars,demon,levelID,verifiedCoins,featureScore,password,dailyNumber`
+/// with the password decoded
+fn rating_hash(field: impl Fn(&str) -> String) -> String {
+    let password = field("27");
+    let password = crypto::xor_base64_decode(&password, crypto::LEVEL_PASSWORD_KEY)
+        .unwrap_or(password);
+    let data = [field("6"), field("18"), field("17"), field("1"), field("38"), field("19"), password, field("41")];
+    crypto::salted_sha1(&data.join(","), crypto::LEVEL_SALT)
+}
+
+fn join_response_fields(fields: &[(&str, String)]) -> String {
+    fields.iter()
+        .flat_map(|(key, value)| [*key, value.as_str()])
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LevelIdentity {
+    pub name: String,
+    pub creator: String,
+    pub original_id: Option<i32>
+}
+
+impl fmt::Display for LevelIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} by {}", self.name, self.creator)?;
+        if let Some(original_id) = self.original_id {
+            write!(f, " (copy of {original_id})")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use serde::{Serialize, Deserialize};
+use crate::{
+    search::PageInfo,
+    serde::{Value, robtop, error::DeResult}
+};
+
+/// Level list of 2.2, stored under `LLM_03` of CCLocalLevels.dat and also
+/// deserializable from getGJLevelLists responses with `serde::robtop`, whose
+/// keys are listed as aliases
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LevelList {
+    /// Only assigned on upload
+    #[serde(rename = "k1", alias = "1")]
+    #[serde(default)]
+    id: Option<i32>,
+    #[serde(rename = "k2", alias = "2")]
+    #[serde(deserialize_with = "robtop::deserialize_string")]
+    name: String,
+    /// Base64, as in saves and responses
+    #[serde(rename = "k3", alias = "3")]
+    #[serde(default, deserialize_with = "robtop::deserialize_string")]
+    description: String,
+    #[serde(rename = "k5", alias = "50")]
+    #[serde(default, deserialize_with = "robtop::deserialize_string")]
+    creator: String,
+    /// Level IDs in the order of the list, stored comma separated
+    #[serde(rename = "k96", alias = "51")]
+    #[serde(default)]
+    #[serde(serialize_with = "robtop::serialize_comma_list", deserialize_with = "robtop::deserialize_comma_list")]
+    level_ids: Vec<i32>,
+    /// Diamonds rewarded for completing `reward_threshold` levels
+    #[serde(rename = "k97", alias = "55")]
+    #[serde(default)]
+    diamonds: i32,
+    #[serde(rename = "k98", alias = "56")]
+    #[serde(default)]
+    reward_threshold: i32,
+    /// Keys not modeled above, kept so that saving doesn't drop them
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>
+}
+
+impl LevelList {
+    pub fn new(name: &str, creator: &str) -> Self {
+        Self {
+            id: None,
+            name: name.to_string(),
+            description: String::new(),
+            creator: creator.to_string(),
+            level_ids: vec![],
+            diamonds: 0,
+            reward_threshold: 0,
+            other: BTreeMap::new()
+        }
+    }
+
+    pub fn id(&self) -> Option<i32> {
+        self.id.filter(|id| *id > 0)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn creator(&self) -> &str {
+        &self.creator
+    }
+
+    /// Decoded description, empty if it isn't valid base64
+    pub fn description(&self) -> String {
+        URL_SAFE.decode(&self.description).ok()
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set_description(&mut self, description: &str) {
+        self.description = URL_SAFE.encode(description);
+    }
+
+    pub fn level_ids(&self) -> &[i32] {
+        &self.level_ids
+    }
+
+    pub fn level_ids_mut(&mut self) -> &mut Vec<i32> {
+        &mut self.level_ids
+    }
+
+    pub fn diamonds(&self) -> i32 {
+        self.diamonds
+    }
+
+    /// Amount of levels that have to be completed to get the diamonds
+    pub fn reward_threshold(&self) -> i32 {
+        self.reward_threshold
+    }
+
+    /// Sets the diamonds rewarded for completing `threshold` levels
+    pub fn set_reward(&mut self, diamonds: i32, threshold: i32) {
+        self.diamonds = diamonds;
+        self.reward_threshold = threshold;
+    }
+
+    /// Whether completing the levels `is_completed` returns true for earns
+    /// the diamonds
+    pub fn is_rewarded(&self, is_completed: impl Fn(i32) -> bool) -> bool {
+        let completed = self.level_ids.iter().filter(|id| is_completed(**id)).count();
+        self.diamonds > 0 && completed >= usize::try_from(self.reward_threshold).unwrap_or_default()
+    }
+}
+
+/// getGJLevelLists response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListPage {
+    pub lists: Vec<LevelList>,
+    pub page: PageInfo
+}
+
+impl ListPage {
+    /// Reads the `#` separated lists, creators and pagination. The creator
+    /// names are already part of the lists. The `-1` the server answers with
+    /// when nothing matches is an empty page
+    pub fn from_response(response: &str) -> DeResult<Self> {
+        let response = response.trim();
+        if response == "-1" {
+            return Ok(Self::default())
+        }
+        let mut sections = robtop::sections(response);
+        let lists = robtop::from_str(sections.next().unwrap_or_default())?;
+        let page = match sections.nth(1).unwrap_or_default() {
+            "" => PageInfo::default(),
+            page => PageInfo::from_response(page)?
+        };
+        Ok(Self { lists, page })
+    }
+}
+
+#[cfg(test)]
+mod tests;
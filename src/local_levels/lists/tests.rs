@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+use super::*;
+use crate::{
+    local_levels::LocalLevelsDB,
+    serde::{DataWithHeader, from_any_reader, ser::to_xml, value::from_value}
+};
+
+fn save(lists: Option<Value>) -> LocalLevelsDB {
+    let mut save = BTreeMap::from([
+        ("LLM_01".to_string(), Value::Array(vec![])),
+        ("LLM_02".to_string(), Value::Integer(42))
+    ]);
+    if let Some(lists) = lists {
+        save.insert("LLM_03".to_string(), lists);
+    }
+    from_value(Value::Dict(save)).unwrap()
+}
+
+fn round_trip(save: &LocalLevelsDB) -> (Vec<u8>, LocalLevelsDB) {
+    let xml = to_xml(&DataWithHeader::new(save.clone())).unwrap();
+    let read = from_any_reader(xml.as_slice()).unwrap().t;
+    (xml, read)
+}
+
+#[test]
+fn round_trips_local_lists() {
+    let mut list = LevelList::new("Hard levels", "Tester");
+    list.set_description("Good luck");
+    list.level_ids_mut().extend([128, 4284013, 71]);
+    list.set_reward(50, 2);
+    let mut local_levels = save(None);
+    local_levels.lists_mut().push(list.clone());
+
+    let (xml, read) = round_trip(&local_levels);
+    assert!(String::from_utf8(xml).unwrap().contains("<s>128,4284013,71</s>"));
+    assert_eq!(read.lists(), [list]);
+    assert_eq!(read.lists()[0].description(), "Good luck");
+}
+
+#[test]
+fn keeps_saves_without_lists_unchanged() {
+    let (xml, read) = round_trip(&save(None));
+    assert!(!String::from_utf8(xml).unwrap().contains("LLM_03"));
+    assert!(read.lists().is_empty());
+}
+
+#[test]
+fn parses_list_page() {
+    let response = concat!(
+        "1:1234:2:Hard levels:3:SGFyZCBsZXZlbHM=:5:1:49:161:50:Tester:10:300:7:8:14:25:19:0:51:128,4284013:55:50:56:2",
+        "|1:1235:2:Single:49:71:50:RobTop:51:71",
+        "#161:Tester:161|71:RobTop:71",
+        "#2:0:10",
+        "#a1b2c3"
+    );
+    let page = ListPage::from_response(response).unwrap();
+    let list = &page.lists[0];
+    assert_eq!((list.id(), list.name(), list.creator()), (Some(1234), "Hard levels", "Tester"));
+    assert_eq!(list.description(), "Hard levels");
+    assert_eq!(list.level_ids(), [128, 4284013]);
+    assert_eq!((list.diamonds(), list.reward_threshold()), (50, 2));
+    assert!(list.is_rewarded(|id| id == 128 || id == 4284013));
+    assert!(!list.is_rewarded(|id| id == 128));
+    assert_eq!(page.lists[1].level_ids(), [71]);
+    assert_eq!(page.page, PageInfo { total: 2, offset: 0, count: 10 });
+    assert_eq!(ListPage::from_response("-1").unwrap(), ListPage::default());
+}
@@ -0,0 +1,139 @@
+use std::{collections::{BTreeSet, HashMap}, fmt};
+use super::{LocalLevelsDB, Level, LevelIdentity};
+
+/// Level with keys that were changed differently on both sides, the merged
+/// save keeps `ours` for those keys and the changes of both sides for the rest
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    pub identity: LevelIdentity,
+    /// `None` if the level was created on both sides
+    pub base: Option<Level>,
+    pub ours: Level,
+    pub theirs: Level,
+    /// Plist keys of the conflicting fields, like `k4` for the level data
+    pub keys: Vec<String>
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} was edited on both sides", self.identity)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    pub merged: LocalLevelsDB,
+    pub conflicts: Vec<MergeConflict>
+}
+
+impl MergeResult {
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+fn index(levels: &[Level]) -> HashMap<LevelIdentity, &Level> {
+    let mut index = HashMap::new();
+    for level in levels {
+        index.entry(level.identity()).or_insert(level);
+    }
+    index
+}
+
+/// Takes the side that changed the field since `base`, `None` if both
+/// changed it differently
+fn merge_field<T: PartialEq>(base: Option<T>, ours: T, theirs: T) -> Option<T> {
+    if ours == theirs || base.as_ref() == Some(&theirs) {
+        Some(ours)
+    } else if base.as_ref() == Some(&ours) {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+/// Merges a level present on both sides field by field, along with the keys
+/// that conflicted. Name, creator and original ID are the same on both sides
+/// since they make up the identity
+fn merge_level(base: Option<&Level>, ours: &Level, theirs: &Level) -> (Level, Vec<String>) {
+    let mut merged = ours.clone();
+    let mut keys = vec![];
+    macro_rules! merge_fields {
+        ($($field:ident: $key:literal),*) => {$(
+            match merge_field(base.map(|base| &base.$field), &ours.$field, &theirs.$field) {
+                Some(value) => merged.$field = value.clone(),
+                None => keys.push(String::from($key))
+            }
+        )*};
+    }
+    merge_fields!(id: "k1", level_string: "k4", player_id: "k6", custom_song_id: "k45");
+
+    let other_keys: BTreeSet<&String> = ours.other.keys().chain(theirs.other.keys()).collect();
+    for key in other_keys {
+        let base_value = base.map(|base| base.other.get(key));
+        match merge_field(base_value, ours.other.get(key), theirs.other.get(key)) {
+            Some(Some(value)) => { merged.other.insert(key.clone(), value.clone()); }
+            Some(None) => { merged.other.remove(key); }
+            None => keys.push(key.clone())
+        }
+    }
+    (merged, keys)
+}
+
+/// Three-way merge of the local levels of two saves that diverged from `base`
+///
+/// Levels are matched by `Level::identity`. Levels added on either side are
+/// kept, levels deleted on one side are dropped unless the other side edited
+/// them. Levels kept on both sides are merged key by key, a key changed
+/// differently on both sides is a conflict and keeps the value of `ours`. Keys
+/// outside of the level list are taken from `ours`
+pub fn merge(base: &LocalLevelsDB, ours: &LocalLevelsDB, theirs: &LocalLevelsDB) -> MergeResult {
+    let base_levels = index(base.levels());
+    let our_levels = index(ours.levels());
+    let their_levels = index(theirs.levels());
+    let mut levels = vec![];
+    let mut conflicts = vec![];
+
+    for our_level in ours.levels() {
+        let identity = our_level.identity();
+        let base_level = base_levels.get(&identity).copied();
+        match (base_level, their_levels.get(&identity).copied()) {
+            (base_level, Some(their_level)) => {
+                let (level, keys) = merge_level(base_level, our_level, their_level);
+                if !keys.is_empty() {
+                    conflicts.push(MergeConflict {
+                        identity,
+                        base: base_level.cloned(),
+                        ours: our_level.clone(),
+                        theirs: their_level.clone(),
+                        keys
+                    });
+                }
+                levels.push(level);
+            }
+            // deleted by them, kept only if we edited it since
+            (Some(base_level), None) => if base_level != our_level {
+                levels.push(our_level.clone());
+            }
+            (None, None) => levels.push(our_level.clone())
+        }
+    }
+
+    for their_level in theirs.levels() {
+        let identity = their_level.identity();
+        if our_levels.contains_key(&identity) {
+            continue
+        }
+        match base_levels.get(&identity) {
+            Some(base_level) if *base_level == their_level => {}
+            _ => levels.push(their_level.clone())
+        }
+    }
+
+    let mut merged = ours.clone();
+    merged.local_levels = levels;
+    MergeResult { merged, conflicts }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use super::*;
+use crate::serde::{Value, value::from_value};
+
+fn level(name: &str, level_string: &str) -> Value {
+    Value::Dict(BTreeMap::from([
+        ("k2".to_string(), Value::String(name.to_string())),
+        ("k4".to_string(), Value::String(level_string.to_string())),
+        ("k5".to_string(), Value::String("Tester".to_string()))
+    ]))
+}
+
+fn save(levels: &[(&str, &str)]) -> LocalLevelsDB {
+    from_value(Value::Dict(BTreeMap::from([
+        ("LLM_01".to_string(), Value::Array(levels.iter().map(|(name, data)| level(name, data)).collect())),
+        ("LLM_02".to_string(), Value::Integer(35))
+    ]))).unwrap()
+}
+
+fn with_description(mut save: LocalLevelsDB, description: &str) -> LocalLevelsDB {
+    for level in &mut save.local_levels {
+        level.other.insert("k3".to_string(), Value::String(description.to_string()));
+    }
+    save
+}
+
+fn description(level: &Level) -> Option<&Value> {
+    level.other.get("k3")
+}
+
+fn merged_levels(result: &MergeResult) -> Vec<(&str, &str)> {
+    result.merged.levels().iter()
+        .map(|level| (level.name(), level.level_string().unwrap()))
+        .collect()
+}
+
+#[test]
+fn unions_added_levels_and_applies_edits() {
+    let base = save(&[("Shared", "a"), ("Edited", "a")]);
+    let ours = save(&[("Shared", "a"), ("Edited", "a"), ("Mine", "m")]);
+    let theirs = save(&[("Edited", "b"), ("Shared", "a"), ("Yours", "y")]);
+
+    let result = merge(&base, &ours, &theirs);
+    assert!(!result.has_conflicts());
+    assert_eq!(merged_levels(&result), [("Shared", "a"), ("Edited", "b"), ("Mine", "m"), ("Yours", "y")]);
+}
+
+#[test]
+fn drops_deleted_levels_unless_edited() {
+    let base = save(&[("Deleted", "a"), ("Edited", "a")]);
+    let ours = save(&[("Deleted", "a"), ("Edited", "b")]);
+    let theirs = save(&[]);
+
+    let result = merge(&base, &ours, &theirs);
+    assert!(!result.has_conflicts());
+    assert_eq!(merged_levels(&result), [("Edited", "b")]);
+}
+
+#[test]
+fn reports_conflicting_level_data() {
+    let base = save(&[("Level", "a")]);
+    let ours = save(&[("Level", "b"), ("New", "x")]);
+    let theirs = save(&[("Level", "c"), ("New", "y")]);
+
+    let result = merge(&base, &ours, &theirs);
+    assert_eq!(merged_levels(&result), [("Level", "b"), ("New", "x")]);
+    assert_eq!(result.conflicts.len(), 2);
+    assert_eq!(result.conflicts[0].to_string(), r#""Level" by Tester was edited on both sides"#);
+    assert_eq!(result.conflicts[0].base.as_ref().and_then(Level::level_string), Some("a"));
+    assert_eq!(result.conflicts[0].theirs.level_string(), Some("c"));
+    assert_eq!(result.conflicts[0].keys, ["k4"]);
+    assert!(result.conflicts[1].base.is_none());
+}
+
+#[test]
+fn same_edit_on_both_sides_isnt_a_conflict() {
+    let base = save(&[("Level", "a")]);
+    let edited = save(&[("Level", "b")]);
+    let result = merge(&base, &edited, &edited);
+    assert!(!result.has_conflicts());
+    assert_eq!(result.merged, edited);
+}
+
+#[test]
+fn keeps_our_metadata_when_they_changed_the_data() {
+    let base = with_description(save(&[("Level", "a")]), "old");
+    let ours = with_description(save(&[("Level", "a")]), "new");
+    let theirs = with_description(save(&[("Level", "b")]), "old");
+
+    let result = merge(&base, &ours, &theirs);
+    assert!(!result.has_conflicts());
+    assert_eq!(merged_levels(&result), [("Level", "b")]);
+    assert_eq!(description(&result.merged.levels()[0]), Some(&Value::String("new".to_string())));
+}
+
+#[test]
+fn reports_conflicting_metadata() {
+    let base = with_description(save(&[("Level", "a")]), "old");
+    let ours = with_description(save(&[("Level", "a")]), "mine");
+    let theirs = with_description(save(&[("Level", "b")]), "theirs");
+
+    let result = merge(&base, &ours, &theirs);
+    assert_eq!(result.conflicts.len(), 1);
+    assert_eq!(result.conflicts[0].keys, ["k3"]);
+    assert_eq!(merged_levels(&result), [("Level", "b")]);
+    assert_eq!(description(&result.merged.levels()[0]), Some(&Value::String("mine".to_string())));
+}
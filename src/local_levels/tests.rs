@@ -0,0 +1,80 @@
+use super::*;
+use crate::serde::{DataWithHeader, ser::to_xml};
+
+const LEVEL_STRING: &str = concat!(
+    "H4sIAAAAAAAAC6WQwQ3DIAxFF3IlbGNI1FNm6ADeICv08KE",
+    "H4sIAAAAAAAAC6WQwQ3DIAxFF3IlbGNI1FNm6ADeICv08KE",
+    "H4sIAAAAAAAAC6WQwQ3DIAxFF3IlbGNI1FNm6ADeICv08KE"
+);
+
+fn download_response(level_hash: &str) -> String {
+    format!(
+        "1:128:2:Test:4:{LEVEL_STRING}:6:3935672:18:5:17:0:38:1:19:0:27:AwcBBQAHAA==#{level_hash}#{}#3935672:Tester:71",
+        "c79c39be525a00ce1b6c41da3ad176327b4221d8"
+    )
+}
+
+#[test]
+fn parses_download_response() {
+    let level = Level::from_download_response(&download_response("b7ae2eadf10b4d3aa387c87bf80bd7342c0f168f")).unwrap();
+    assert_eq!(level.id(), Some(128));
+    assert_eq!(level.name(), "Test");
+    assert_eq!(level.level_string(), Some(LEVEL_STRING));
+}
+
+#[test]
+fn saves_downloaded_levels_without_response_keys() {
+    let response = download_response("b7ae2eadf10b4d3aa387c87bf80bd7342c0f168f");
+    let level = Level::from_download_response(&response).unwrap();
+    assert_eq!(level.to_upload().password, 1_123_456);
+
+    let mut save: LocalLevelsDB = from_value(Value::Dict(BTreeMap::from([
+        ("LLM_01".to_string(), Value::Array(vec![])),
+        ("LLM_02".to_string(), Value::Integer(35))
+    ]))).unwrap();
+    save.levels_mut().push(level);
+    let xml = String::from_utf8(to_xml(&DataWithHeader::new(save)).unwrap()).unwrap();
+    let keys: Vec<&str> = xml.split("<k>").skip(1).filter_map(|key| key.split_once("</k>")).map(|(key, _)| key).collect();
+    assert!(keys.iter().all(|key| key.parse::<i32>().is_err()), "{keys:?}");
+    assert!(keys.contains(&"k41"));
+}
+
+#[test]
+fn hashes_whole_short_level_strings() {
+    let response = concat!(
+        "1:129:2:Short:4:H4sIAAAA:6:71",
+        "#b9dd1645a044f8a90db3e4aa169d5a63b9f1dde6",
+        "#0c3e38364850db03fb100b4895fb9c774726422e"
+    );
+    assert_eq!(Level::from_download_response(response).unwrap().level_string(), Some("H4sIAAAA"));
+}
+
+#[test]
+fn rejects_mismatching_hashes() {
+    let err = Level::from_download_response(&download_response("0000")).unwrap_err();
+    assert!(matches!(err, DeError::HashMismatch { found, .. } if found == "0000"));
+
+    let tampered = download_response("b7ae2eadf10b4d3aa387c87bf80bd7342c0f168f").replace(":18:5:", ":18:10:");
+    assert!(matches!(Level::from_download_response(&tampered), Err(DeError::HashMismatch { .. })));
+    assert!(Level::from_download_response("1:128:2:Test").is_err());
+}
+
+#[test]
+fn builds_upload_request_from_save_fields() {
+    let level: Level = robtop::from_str(&format!(
+        "k2:Test:k3:QSBsZXZlbA==:k4:{LEVEL_STRING}:k16:3:k23:2:k8:4:k45:0:k48:1200:k64:2:k66:7:k41:123456:k42:99:k72:1"
+    )).unwrap();
+    let upload = level.to_upload();
+    assert_eq!(upload.description, "A level");
+    assert_eq!((upload.version, upload.length, upload.audio_track, upload.song_id), (3, 2, 4, 0));
+    assert_eq!((upload.objects, upload.coins, upload.requested_stars), (1200, 2, 7));
+    assert_eq!((upload.password, upload.original_id), (1_123_456, 99));
+    assert!(upload.ldm && !upload.unlisted && !upload.two_player);
+
+    let account = Account::new(161, "Tester", "password", "S1234", 71);
+    let request = level.to_upload_request(&account);
+    assert_eq!(request.form.get("levelString"), Some(LEVEL_STRING));
+    assert_eq!(request.form.get("seed2"), Some("AgFTBQJVUwIBAwYEUQICUgALBgVRAAdWAQQHUAcGDVdTAVcFV1FVBA=="));
+    assert_eq!(request.form.get("levelDesc"), Some("QSBsZXZlbA=="));
+    assert_eq!(request, level.to_upload_request(&account));
+}
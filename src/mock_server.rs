@@ -0,0 +1,250 @@
+use std::{collections::HashMap, fs, path::Path};
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use crate::{
+    crypto,
+    local_levels::{Level, LocalLevelsDB},
+    requests::{LevelUpload, decode_component},
+    search::Creator,
+    songs::{SongInfo, SongLibrary},
+    serde::{
+        from_file,
+        error::{DeError, DeResult}
+    }
+};
+
+/// Levels per page of getGJLevels21
+pub const PAGE_SIZE: usize = 10;
+
+/// What the servers answer with when a request fails or finds nothing
+const FAILURE: &str = "-1";
+
+/// In-memory stand-in for the `/database` endpoints of a GD server, serving
+/// local levels with the responses the real servers send. Requests are
+/// trusted, only the `seed2` of uploads is checked
+#[derive(Debug, Clone, Default)]
+pub struct MockServer {
+    levels: Vec<Level>,
+    creators: Vec<Creator>,
+    songs: SongLibrary
+}
+
+impl MockServer {
+    /// Serves the levels, giving those that were never uploaded an ID and a
+    /// player for each creator name
+    pub fn new(levels: Vec<Level>) -> Self {
+        let mut server = Self::default();
+        for mut level in levels {
+            if level.id().is_none_or(|id| id <= 0) {
+                level.set_id(server.next_level_id());
+            }
+            let player_id = match level.player_id() {
+                Some(player_id) => player_id,
+                None => server.creators.iter()
+                    .find(|creator| creator.name == level.creator())
+                    .map_or_else(|| server.next_player_id(), |creator| creator.player_id)
+            };
+            level.set_player_id(player_id);
+            server.add_creator(player_id, level.creator(), player_id);
+            server.levels.push(level);
+        }
+        server
+    }
+
+    pub fn from_local_levels(local_levels: &LocalLevelsDB) -> Self {
+        Self::new(local_levels.levels().to_vec())
+    }
+
+    /// Serves every `.gmd` file of the directory, in the order of their names
+    pub fn from_gmd_dir<P: AsRef<Path>>(dir: P) -> DeResult<Self> {
+        let mut paths: Vec<_> = fs::read_dir(dir).map_err(DeError::Io)?
+            .map(|entry| entry.map(|entry| entry.path()).map_err(DeError::Io))
+            .collect::<DeResult<_>>()?;
+        paths.retain(|path| path.extension().is_some_and(|extension| extension == "gmd"));
+        paths.sort();
+        let levels = paths.iter()
+            .map(|path| from_file::<Level, _>(path).map(|level| level.t))
+            .collect::<DeResult<_>>()?;
+        Ok(Self::new(levels))
+    }
+
+    /// Custom songs sent along with the levels using them in searches
+    pub fn with_songs(mut self, songs: SongLibrary) -> Self {
+        self.songs = songs;
+        self
+    }
+
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+
+    pub fn level(&self, id: i32) -> Option<&Level> {
+        self.levels.iter().find(|level| level.id() == Some(id))
+    }
+
+    /// Answers a request to a path like `/database/getGJLevels21.php` with
+    /// its form-encoded body, `None` for endpoints that aren't implemented.
+    /// A query string after the path is ignored
+    pub fn handle(&mut self, path: &str, body: &str) -> Option<String> {
+        let path = path.split_once('?').map_or(path, |(path, _)| path);
+        let form = parse_form(body);
+        let field = |key: &str| form.get(key).map_or("", String::as_str);
+        let int_field = |key: &str| field(key).parse::<i32>().unwrap_or_default();
+        let endpoint = path.rsplit('/').next().unwrap_or_default();
+        let response = match endpoint {
+            "getGJLevels21.php" => self.search(field("str"), int_field("type"), int_field("page")),
+            "downloadGJLevel22.php" => self.level(int_field("levelID"))
+                .map(Level::to_download_response),
+            "uploadGJLevel21.php" => self.upload(&form),
+            "getGJUserInfo20.php" => self.user_info(int_field("targetAccountID")),
+            _ => return None
+        };
+        Some(response.unwrap_or_else(|| String::from(FAILURE)))
+    }
+
+    /// Search type 5 lists the levels of a player, other types match the
+    /// query against level IDs and names. Unlisted levels are only found by
+    /// their ID, custom songs the server doesn't know are left out
+    fn search(&self, query: &str, search_type: i32, page: i32) -> Option<String> {
+        let query_id = query.parse::<i32>().ok();
+        let query = query.to_lowercase();
+        let matches: Vec<&Level> = self.levels.iter()
+            .filter(|level| match search_type {
+                5 => level.player_id() == query_id,
+                _ if query_id.is_some() => level.id() == query_id,
+                _ => !level.to_upload().unlisted && level.name().to_lowercase().contains(&query)
+            })
+            .collect();
+        let offset = usize::try_from(page).unwrap_or_default() * PAGE_SIZE;
+        let levels: Vec<&Level> = matches.iter().skip(offset).take(PAGE_SIZE).copied().collect();
+        if levels.is_empty() {
+            return None
+        }
+
+        let mut creators: Vec<&Creator> = vec![];
+        for player_id in levels.iter().filter_map(|level| level.player_id()) {
+            let creator = self.creators.iter().find(|creator| creator.player_id == player_id);
+            if let Some(creator) = creator.filter(|creator| !creators.contains(creator)) {
+                creators.push(creator);
+            }
+        }
+        let mut songs: Vec<&SongInfo> = vec![];
+        for song in levels.iter().filter_map(|level| self.songs.level_song(level)) {
+            if !songs.contains(&song) {
+                songs.push(song);
+            }
+        }
+        let hashed: Vec<_> = levels.iter()
+            .map(|level| (level.id().unwrap_or_default(), 0, 0))
+            .collect();
+        Some(format!(
+            "{}#{}#{}#{}:{offset}:{PAGE_SIZE}#{}",
+            levels.iter().map(|level| level.to_search_response()).collect::<Vec<_>>().join("|"),
+            creators.iter()
+                .map(|creator| format!("{}:{}:{}", creator.player_id, creator.name, creator.account_id))
+                .collect::<Vec<_>>()
+                .join("|"),
+            songs.iter().filter_map(|song| song.to_response().ok()).collect::<Vec<_>>().join("~:~"),
+            matches.len(),
+            crypto::search_page_hash(&hashed)
+        ))
+    }
+
+    /// Stores the level and answers with its ID. Updating a level of
+    /// someone else or sending a `seed2` that doesn't match the level data
+    /// fails like on the real servers
+    fn upload(&mut self, form: &HashMap<String, String>) -> Option<String> {
+        let field = |key: &str| form.get(key).map_or("", String::as_str);
+        let int_field = |key: &str| field(key).parse::<i32>().unwrap_or_default();
+        let upload = LevelUpload {
+            level_id: int_field("levelID"),
+            name: field("levelName").to_string(),
+            description: URL_SAFE.decode(field("levelDesc")).ok()
+                .and_then(|description| String::from_utf8(description).ok())
+                .unwrap_or_default(),
+            version: int_field("levelVersion"),
+            length: int_field("levelLength"),
+            audio_track: int_field("audioTrack"),
+            song_id: int_field("songID"),
+            objects: int_field("objects"),
+            coins: int_field("coins"),
+            requested_stars: int_field("requestedStars"),
+            unlisted: int_field("unlisted") != 0,
+            ldm: int_field("ldm") != 0,
+            password: int_field("password"),
+            original_id: int_field("original"),
+            two_player: int_field("twoPlayer") != 0,
+            level_string: field("levelString").to_string()
+        };
+        if field("seed2") != crypto::level_seed(&upload.level_string) {
+            return None
+        }
+
+        let player_id = int_field("uuid");
+        let mut level = Level::from_upload(&upload, field("userName"), player_id);
+        let existing = self.levels.iter().position(|level| level.id() == Some(upload.level_id));
+        let id = match existing {
+            Some(index) if self.levels[index].player_id() == Some(player_id) => {
+                self.levels[index] = level;
+                upload.level_id
+            }
+            Some(_) => return None,
+            None => {
+                let id = self.next_level_id();
+                level.set_id(id);
+                self.levels.push(level);
+                id
+            }
+        };
+        self.add_creator(player_id, field("userName"), int_field("accountID"));
+        Some(id.to_string())
+    }
+
+    /// Profile with the creator points set to the amount of levels, every
+    /// other stat is 0 and every icon the default one
+    fn user_info(&self, account_id: i32) -> Option<String> {
+        let creator = self.creators.iter().find(|creator| creator.account_id == account_id)?;
+        let levels = self.levels.iter()
+            .filter(|level| level.player_id() == Some(creator.player_id))
+            .count();
+        Some(format!(
+            concat!(
+                "1:{}:2:{}:13:0:17:0:10:0:11:3:51:0:3:0:52:0:46:0:4:0:8:{}:18:0:19:0:50:0:20::",
+                "21:1:22:1:23:1:24:1:25:1:26:1:28:0:43:1:48:1:30:0:16:{}:31:0:44::45::49:0:29:1"
+            ),
+            creator.name, creator.player_id, levels, creator.account_id
+        ))
+    }
+
+    fn add_creator(&mut self, player_id: i32, name: &str, account_id: i32) {
+        match self.creators.iter_mut().find(|creator| creator.player_id == player_id) {
+            Some(creator) => {
+                creator.name = name.to_string();
+                creator.account_id = account_id;
+            }
+            None => self.creators.push(Creator { player_id, name: name.to_string(), account_id })
+        }
+    }
+
+    fn next_level_id(&self) -> i32 {
+        self.levels.iter().filter_map(Level::id).max().unwrap_or_default().max(0) + 1
+    }
+
+    fn next_player_id(&self) -> i32 {
+        self.creators.iter().map(|creator| creator.player_id).max().unwrap_or_default().max(0) + 1
+    }
+}
+
+/// Fields of an `application/x-www-form-urlencoded` body, the inverse of
+/// `requests::Form`'s Display
+fn parse_form(body: &str) -> HashMap<String, String> {
+    body.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode_component(key), decode_component(value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
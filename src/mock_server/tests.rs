@@ -0,0 +1,145 @@
+use super::*;
+use crate::{
+    requests::{self, Account, LevelSearch},
+    search::SearchPage,
+    songs::{SongInfo, SongLibrary},
+    serde::{DataWithHeader, robtop, ser::to_xml},
+    users::UserProfile
+};
+
+fn level(fields: &str) -> Level {
+    robtop::from_str(fields).unwrap()
+}
+
+fn server() -> MockServer {
+    MockServer::new(vec![
+        level("k2:First:k3:QSBsZXZlbA==:k4:H4sIAAAAAAAAC6WQwQ3DIAxFF3IlbGNI1FNm6ADeICv08KE:k5:Tester:k48:1200"),
+        level("k2:Second:k4:H4sIAAAA:k5:RobTop:k45:803223"),
+        level("k2:Hidden:k5:Tester:k79:1")
+    ])
+}
+
+fn account() -> Account {
+    Account::new(161, "Uploader", "password", "S1234", 9000)
+}
+
+#[test]
+fn assigns_ids_and_players() {
+    let server = server();
+    let ids: Vec<_> = server.levels().iter().map(|level| (level.id(), level.player_id())).collect();
+    assert_eq!(ids, [(Some(1), Some(1)), (Some(2), Some(2)), (Some(3), Some(1))]);
+}
+
+#[test]
+fn answers_searches() {
+    let mut server = server();
+    let search = requests::get_levels(&LevelSearch { query: String::from("s"), ..LevelSearch::default() });
+    let response = server.handle("/database/getGJLevels21.php", &search.body()).unwrap();
+    assert!(response.ends_with(&format!("#2:0:10#{}", crypto::search_page_hash(&[(1, 0, 0), (2, 0, 0)]))));
+
+    let page = SearchPage::from_response(&response).unwrap();
+    let levels: Vec<_> = page.levels.iter().map(|level| (level.name(), level.creator())).collect();
+    assert_eq!(levels, [("First", "Tester"), ("Second", "RobTop")]);
+    assert_eq!(page.levels[1].custom_song_id(), Some(803223));
+    assert_eq!(page.levels[0].level_string(), None);
+    assert_eq!(page.page.total, 2);
+
+    let hidden = requests::get_levels(&LevelSearch { query: String::from("3"), ..LevelSearch::default() });
+    let page = SearchPage::from_response(&server.handle("getGJLevels21.php", &hidden.body()).unwrap()).unwrap();
+    assert_eq!(page.levels[0].name(), "Hidden");
+
+    let by_player = requests::get_levels(&LevelSearch { query: String::from("1"), search_type: 5, page: 0 });
+    let page = SearchPage::from_response(&server.handle("getGJLevels21.php", &by_player.body()).unwrap()).unwrap();
+    assert_eq!(page.levels.len(), 2);
+
+    let with_query = server.handle("/database/getGJLevels21.php?gameVersion=22", &search.body());
+    assert_eq!(with_query.as_deref(), Some(response.as_str()));
+
+    let missing = requests::get_levels(&LevelSearch { query: String::from("nothing"), ..LevelSearch::default() });
+    assert_eq!(server.handle("getGJLevels21.php", &missing.body()).as_deref(), Some("-1"));
+}
+
+#[test]
+fn sends_custom_songs_of_the_page() {
+    let song = SongInfo::from_response(concat!(
+        "1~|~803223~|~2~|~Supernova~|~3~|~12~|~4~|~Xtrullor~|~5~|~9.56",
+        "~|~10~|~https%3A%2F%2Faudio.ngfiles.com%2F803000%2F803223_Supernova.mp3%3Ff1"
+    )).unwrap();
+    let mut songs = SongLibrary::default();
+    songs.insert(song.clone());
+    let mut server = server().with_songs(songs);
+
+    let search = requests::get_levels(&LevelSearch { query: String::from("s"), ..LevelSearch::default() });
+    let response = server.handle("getGJLevels21.php", &search.body()).unwrap();
+    let page = SearchPage::from_response(&response).unwrap();
+    assert_eq!(page.songs, [song]);
+    assert_eq!(page.song(803223).map(|song| song.name.as_str()), Some("Supernova"));
+
+    let first = requests::get_levels(&LevelSearch { query: String::from("1"), ..LevelSearch::default() });
+    let page = SearchPage::from_response(&server.handle("getGJLevels21.php", &first.body()).unwrap()).unwrap();
+    assert!(page.songs.is_empty());
+}
+
+#[test]
+fn answers_downloads_with_valid_hashes() {
+    let mut server = server();
+    let request = requests::download_level(1, None, false, "");
+    let response = server.handle("/database/downloadGJLevel22.php", &request.body()).unwrap();
+    let level = Level::from_download_response(&response).unwrap();
+    assert_eq!(level.name(), "First");
+    assert_eq!(level.level_string(), server.level(1).unwrap().level_string());
+
+    let request = requests::download_level(99, None, false, "");
+    assert_eq!(server.handle("/database/downloadGJLevel22.php", &request.body()).as_deref(), Some("-1"));
+}
+
+#[test]
+fn stores_uploads() {
+    let mut server = server();
+    let mut level = level("k2:New level:k3:TmV3:k4:H4sIAAAAAAAAA:k16:2:k41:123456:k8:3");
+    let response = server.handle("/database/uploadGJLevel21.php", &level.to_upload_request(&account()).body());
+    assert_eq!(response.as_deref(), Some("4"));
+
+    let download = server.handle("downloadGJLevel22.php", &requests::download_level(4, None, false, "").body());
+    let uploaded = Level::from_download_response(&download.unwrap()).unwrap();
+    assert_eq!((uploaded.name(), uploaded.level_string()), ("New level", Some("H4sIAAAAAAAAA")));
+    assert_eq!(server.level(4).unwrap().to_upload(), LevelUpload { level_id: 4, ..level.to_upload() });
+
+    level.set_id(1);
+    let response = server.handle("uploadGJLevel21.php", &level.to_upload_request(&account()).body());
+    assert_eq!(response.as_deref(), Some("-1"));
+    let body = level.to_upload_request(&account()).body().replace("seed2=", "seed2=x");
+    assert_eq!(server.handle("uploadGJLevel21.php", &body).as_deref(), Some("-1"));
+}
+
+#[test]
+fn answers_user_info() {
+    let mut server = server();
+    server.handle("uploadGJLevel21.php", &level("k2:Mine:k4:H4sI").to_upload_request(&account()).body());
+    let response = server.handle("getGJUserInfo20.php", &requests::get_user_info(161, None).body()).unwrap();
+    let profile = UserProfile::from_response(&response).unwrap();
+    assert_eq!((profile.name.as_str(), profile.player_id, profile.creator_points), ("Uploader", 9000, 1));
+    assert_eq!(server.handle("getGJUserInfo20.php", "targetAccountID=5").as_deref(), Some("-1"));
+    assert_eq!(server.handle("getGJComments21.php", ""), None);
+}
+
+#[test]
+fn loads_gmd_files() {
+    let dir = tempfile::tempdir().unwrap();
+    for (file, name) in [("b.gmd", "Second"), ("a.gmd", "First")] {
+        let xml = to_xml(&DataWithHeader::new(level(&format!("k2:{name}:k5:Tester")))).unwrap();
+        fs::write(dir.path().join(file), xml).unwrap();
+    }
+    fs::write(dir.path().join("notes.txt"), "not a level").unwrap();
+    let server = MockServer::from_gmd_dir(dir.path()).unwrap();
+    let names: Vec<_> = server.levels().iter().map(Level::name).collect();
+    assert_eq!(names, ["First", "Second"]);
+}
+
+#[test]
+fn decodes_form_components() {
+    let form = parse_form("levelName=A+b%26c&levelDesc=&empty");
+    assert_eq!(form["levelName"], "A b&c");
+    assert_eq!(form["levelDesc"], "");
+    assert_eq!(form["empty"], "");
+}
@@ -0,0 +1,139 @@
+use std::fmt::{self, Display};
+use serde::{Serialize, Deserialize};
+
+/// `valueKeeper` keys of the toggles covered by `Options`
+pub(crate) mod game_variables {
+    pub const AUTO_RETRY: &str = "gv_0026";
+    pub const AUTO_CHECKPOINTS: &str = "gv_0027";
+    pub const FAST_PRACTICE_RESET: &str = "gv_0052";
+    pub const FLIP_TWO_PLAYER_CONTROLS: &str = "gv_0010";
+    pub const SHOW_CURSOR: &str = "gv_0024";
+    pub const SHOW_PERCENTAGE: &str = "gv_0040";
+    pub const DISABLE_THUMBSTICK: &str = "gv_0028";
+    pub const SMOOTH_FIX: &str = "gv_0023";
+    pub const EDITOR_SWIPE: &str = "gv_0001";
+    pub const EDITOR_INCREASE_MAX_UNDO: &str = "gv_0013";
+}
+
+/// Settings of CCGameManager.dat grouped the way the options menu does
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub audio: AudioOptions,
+    pub graphics: GraphicsOptions,
+    pub gameplay: GameplayOptions,
+    pub editor: EditorOptions
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioOptions {
+    pub music_volume: f32,
+    pub sfx_volume: f32
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphicsOptions {
+    pub resolution: i32,
+    pub texture_quality: TextureQuality,
+    pub smooth_fix: bool
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameplayOptions {
+    pub auto_retry: bool,
+    pub auto_checkpoints: bool,
+    pub fast_practice_reset: bool,
+    pub flip_two_player_controls: bool,
+    pub show_cursor: bool,
+    pub show_percentage: bool,
+    pub show_song_markers: bool,
+    pub show_progress_bar: bool,
+    pub disable_thumbstick: bool
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditorOptions {
+    pub swipe: bool,
+    pub increase_max_undo: bool
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(from = "i32", into = "i32")]
+pub enum TextureQuality {
+    Low,
+    Medium,
+    High,
+    /// Quality this version doesn't know, kept so that it's written back as is
+    Other(i32)
+}
+
+impl From<i32> for TextureQuality {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::Low,
+            2 => Self::Medium,
+            3 => Self::High,
+            _ => Self::Other(value)
+        }
+    }
+}
+
+impl From<TextureQuality> for i32 {
+    fn from(quality: TextureQuality) -> Self {
+        match quality {
+            TextureQuality::Low => 1,
+            TextureQuality::Medium => 2,
+            TextureQuality::High => 3,
+            TextureQuality::Other(value) => value
+        }
+    }
+}
+
+/// Single option that differs between two `Options`
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionChange {
+    pub name: &'static str,
+    pub old: String,
+    pub new: String
+}
+
+impl Display for OptionChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.name, self.old, self.new)
+    }
+}
+
+impl Options {
+    fn entries(&self) -> Vec<(&'static str, String)> {
+        let Self { audio, graphics, gameplay, editor } = self;
+        vec![
+            ("audio.music_volume", audio.music_volume.to_string()),
+            ("audio.sfx_volume", audio.sfx_volume.to_string()),
+            ("graphics.resolution", graphics.resolution.to_string()),
+            ("graphics.texture_quality", format!("{:?}", graphics.texture_quality)),
+            ("graphics.smooth_fix", graphics.smooth_fix.to_string()),
+            ("gameplay.auto_retry", gameplay.auto_retry.to_string()),
+            ("gameplay.auto_checkpoints", gameplay.auto_checkpoints.to_string()),
+            ("gameplay.fast_practice_reset", gameplay.fast_practice_reset.to_string()),
+            ("gameplay.flip_two_player_controls", gameplay.flip_two_player_controls.to_string()),
+            ("gameplay.show_cursor", gameplay.show_cursor.to_string()),
+            ("gameplay.show_percentage", gameplay.show_percentage.to_string()),
+            ("gameplay.show_song_markers", gameplay.show_song_markers.to_string()),
+            ("gameplay.show_progress_bar", gameplay.show_progress_bar.to_string()),
+            ("gameplay.disable_thumbstick", gameplay.disable_thumbstick.to_string()),
+            ("editor.swipe", editor.swipe.to_string()),
+            ("editor.increase_max_undo", editor.increase_max_undo.to_string())
+        ]
+    }
+
+    /// Options whose value in `other` differs from `self`
+    pub fn diff(&self, other: &Options) -> Vec<OptionChange> {
+        self.entries().into_iter()
+            .zip(other.entries())
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((name, old), (_, new))| OptionChange { name, old, new })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,67 @@
+use super::*;
+use crate::{
+    game_manager::GameManagerDB,
+    serde::{DataWithHeader, Value, from_any_reader, ser::to_xml}
+};
+
+const GAME_MANAGER: &str = concat!(
+    r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+    "<k>bgVolume</k><r>0.5</r><k>sfxVolume</k><r>1</r><k>playerName</k><s>Tester</s>",
+    "<k>playerUserID</k><i>77</i><k>playerFrame</k><i>12</i><k>playerShip</k><i>3</i>",
+    "<k>playerBall</k><i>4</i><k>playerBird</k><i>5</i><k>playerDart</k><i>6</i>",
+    "<k>playerRobot</k><i>7</i><k>playerSpider</k><i>8</i><k>playerColor</k><i>9</i>",
+    "<k>playerColor2</k><i>12</i><k>playerStreak</k><i>2</i><k>playerDeathEffect</k><i>1</i>",
+    "<k>playerIconType</k><i>1</i><k>bootups</k><i>3</i><k>binaryVersion</k><i>42</i>",
+    "<k>resolution</k><i>-1</i><k>texQuality</k><i>0</i>",
+    "</dict></plist>"
+);
+
+fn standard() -> Options {
+    Options {
+        audio: AudioOptions { music_volume: 0.5, sfx_volume: 1.0 },
+        graphics: GraphicsOptions {
+            resolution: -1,
+            texture_quality: TextureQuality::High,
+            smooth_fix: false
+        },
+        gameplay: GameplayOptions {
+            auto_retry: true,
+            auto_checkpoints: true,
+            fast_practice_reset: false,
+            flip_two_player_controls: false,
+            show_cursor: false,
+            show_percentage: true,
+            show_song_markers: true,
+            show_progress_bar: true,
+            disable_thumbstick: false
+        },
+        editor: EditorOptions { swipe: true, increase_max_undo: false }
+    }
+}
+
+#[test]
+fn diffs_options() {
+    let old = standard();
+    let mut new = standard();
+    assert!(old.diff(&new).is_empty());
+
+    new.audio.music_volume = 0.25;
+    new.graphics.texture_quality = TextureQuality::Low;
+    new.editor.increase_max_undo = true;
+    let changes = old.diff(&new);
+    assert_eq!(changes.iter().map(ToString::to_string).collect::<Vec<_>>(), vec![
+        "audio.music_volume: 0.5 -> 0.25",
+        "graphics.texture_quality: High -> Low",
+        "editor.increase_max_undo: false -> true"
+    ]);
+}
+
+#[test]
+fn keeps_unknown_texture_qualities() {
+    let game_manager: GameManagerDB = from_any_reader(GAME_MANAGER.as_bytes()).unwrap().t;
+    assert_eq!(game_manager.options().graphics.texture_quality, TextureQuality::Other(0));
+
+    let written: Value = from_any_reader(&to_xml(&DataWithHeader::new(game_manager)).unwrap()[..]).unwrap().t;
+    assert_eq!(written.get("texQuality"), Some(&Value::Integer(0)));
+    assert_eq!(TextureQuality::from(2), TextureQuality::Medium);
+}
@@ -0,0 +1,150 @@
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de};
+use crate::{
+    crypto,
+    search::PageInfo,
+    serde::{
+        robtop,
+        error::{DeError, DeResult}
+    }
+};
+
+/// RGB color of a map pack, stored as `r,g,b`
+pub type Rgb = (u8, u8, u8);
+
+/// Map pack of getGJMapPacks21
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MapPack {
+    #[serde(rename = "1")]
+    pub id: i32,
+    #[serde(rename = "2")]
+    #[serde(deserialize_with = "robtop::deserialize_string")]
+    pub name: String,
+    #[serde(rename = "3")]
+    #[serde(serialize_with = "robtop::serialize_comma_list", deserialize_with = "robtop::deserialize_comma_list")]
+    pub level_ids: Vec<i32>,
+    #[serde(rename = "4")]
+    pub stars: i32,
+    #[serde(rename = "5")]
+    pub coins: i32,
+    /// Face shown next to the pack, 0 for auto up to 6 for demon
+    #[serde(rename = "6")]
+    pub difficulty: i32,
+    #[serde(rename = "7")]
+    #[serde(serialize_with = "serialize_rgb", deserialize_with = "deserialize_rgb")]
+    pub text_color: Rgb,
+    #[serde(rename = "8")]
+    #[serde(serialize_with = "serialize_rgb", deserialize_with = "deserialize_rgb")]
+    pub bar_color: Rgb
+}
+
+/// getGJMapPacks21 response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MapPackPage {
+    pub packs: Vec<MapPack>,
+    pub page: PageInfo
+}
+
+impl MapPackPage {
+    /// Reads the packs, the pagination and the hash, which is checked
+    pub fn from_response(response: &str) -> DeResult<Self> {
+        let response = response.trim();
+        if response == "-1" {
+            return Ok(Self::default())
+        }
+        let mut sections = robtop::sections(response);
+        let packs: Vec<MapPack> = robtop::from_str(sections.next().unwrap_or_default())?;
+        let (Some(page), Some(hash)) = (sections.next(), sections.next()) else {
+            return Err(DeError::Custom(String::from("response has no hash")))
+        };
+        let expected = map_pack_hash(&packs);
+        if hash != expected {
+            return Err(DeError::HashMismatch { expected, found: hash.to_string() })
+        }
+        Ok(Self { packs, page: PageInfo::from_response(page)? })
+    }
+
+    /// The response `from_response` reads
+    pub fn to_response(&self) -> String {
+        let packs: Vec<String> = self.packs.iter()
+            .map(|pack| robtop::to_string(pack).expect("map packs serialize"))
+            .collect();
+        let page = self.page;
+        format!("{}#{}:{}:{}#{}", packs.join("|"), page.total, page.offset, page.count, map_pack_hash(&self.packs))
+    }
+}
+
+fn map_pack_hash(packs: &[MapPack]) -> String {
+    let hashed: Vec<_> = packs.iter().map(|pack| (pack.id, pack.stars, pack.coins)).collect();
+    crypto::search_page_hash(&hashed)
+}
+
+/// Names of the gauntlets by ID, starting at 1
+const GAUNTLET_NAMES: [&str; 15] = [
+    "Fire", "Ice", "Poison", "Shadow", "Lava", "Bonus", "Chaos", "Demon",
+    "Time", "Crystal", "Magic", "Spike", "Monster", "Doom", "Death"
+];
+
+/// Gauntlet of getGJGauntlets21
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Gauntlet {
+    #[serde(rename = "1")]
+    pub id: i32,
+    #[serde(rename = "3")]
+    #[serde(serialize_with = "robtop::serialize_comma_list", deserialize_with = "robtop::deserialize_comma_list")]
+    pub level_ids: Vec<i32>
+}
+
+impl Gauntlet {
+    /// Name shown in game, `None` for gauntlets newer than this crate
+    pub fn name(&self) -> Option<&'static str> {
+        usize::try_from(self.id - 1).ok().and_then(|index| GAUNTLET_NAMES.get(index)).copied()
+    }
+}
+
+/// Reads a getGJGauntlets21 response, the gauntlets followed by a hash that
+/// is checked
+pub fn gauntlets_from_response(response: &str) -> DeResult<Vec<Gauntlet>> {
+    let mut sections = robtop::sections(response.trim());
+    let gauntlets: Vec<Gauntlet> = robtop::from_str(sections.next().unwrap_or_default())?;
+    let Some(hash) = sections.next() else {
+        return Err(DeError::Custom(String::from("response has no hash")))
+    };
+    let expected = gauntlet_hash(&gauntlets);
+    if hash != expected {
+        return Err(DeError::HashMismatch { expected, found: hash.to_string() })
+    }
+    Ok(gauntlets)
+}
+
+/// The response `gauntlets_from_response` reads
+pub fn gauntlets_to_response(gauntlets: &[Gauntlet]) -> String {
+    let records: Vec<String> = gauntlets.iter()
+        .map(|gauntlet| robtop::to_string(gauntlet).expect("gauntlets serialize"))
+        .collect();
+    format!("{}#{}", records.join("|"), gauntlet_hash(gauntlets))
+}
+
+/// Salted SHA-1 of every ID followed by its comma separated levels
+fn gauntlet_hash(gauntlets: &[Gauntlet]) -> String {
+    let data: String = gauntlets.iter()
+        .map(|gauntlet| {
+            let levels: Vec<String> = gauntlet.level_ids.iter().map(i32::to_string).collect();
+            format!("{}{}", gauntlet.id, levels.join(","))
+        })
+        .collect();
+    crypto::salted_sha1(&data, crypto::LEVEL_SALT)
+}
+
+fn serialize_rgb<S: Serializer>(&(r, g, b): &Rgb, serializer: S) -> Result<S::Ok, S::Error> {
+    robtop::serialize_comma_list(&[r, g, b], serializer)
+}
+
+fn deserialize_rgb<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rgb, D::Error> {
+    match robtop::deserialize_comma_list::<u8, _>(deserializer)?[..] {
+        [r, g, b] => Ok((r, g, b)),
+        _ => Err(de::Error::custom("color isn't r,g,b"))
+    }
+}
+
+#[cfg(test)]
+mod tests;
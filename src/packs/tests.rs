@@ -0,0 +1,37 @@
+use super::*;
+
+const MAP_PACKS: &str = concat!(
+    "1:1:2:Hard Pack:3:1,2,3:4:4:5:1:6:2:7:255,255,0:8:255,0,0",
+    "|1:23:2:Demon Pack 5:3:4284013,1234:4:10:5:2:6:6:7:0,128,255:8:75,75,75",
+    "#50:0:10#0ea11ad159c0fb76755b6ab6bd9a05a5c691e538"
+);
+
+#[test]
+fn parses_map_packs() {
+    let page = MapPackPage::from_response(MAP_PACKS).unwrap();
+    let pack = &page.packs[1];
+    assert_eq!((pack.id, pack.name.as_str(), pack.stars, pack.coins), (23, "Demon Pack 5", 10, 2));
+    assert_eq!(pack.level_ids, [4284013, 1234]);
+    assert_eq!((pack.text_color, pack.bar_color), ((0, 128, 255), (75, 75, 75)));
+    assert_eq!(page.page, PageInfo { total: 50, offset: 0, count: 10 });
+    assert_eq!(page.to_response(), MAP_PACKS);
+}
+
+#[test]
+fn rejects_mismatching_map_pack_hash() {
+    let tampered = MAP_PACKS.replace(":4:10:", ":4:12:");
+    assert!(matches!(MapPackPage::from_response(&tampered), Err(DeError::HashMismatch { .. })));
+    assert!(MapPackPage::from_response("1:1:2:Pack:3:1:4:1:5:0:6:0:7:0,0,0:8:0,0,0").is_err());
+    assert!(MapPackPage::from_response(&MAP_PACKS.replace("255,255,0", "255,255")).is_err());
+}
+
+#[test]
+fn parses_gauntlets() {
+    let response = "1:1:3:14,18,20|1:3:3:71,72#bcf620729c66d5340e1556b6ab844595090783f2";
+    let gauntlets = gauntlets_from_response(response).unwrap();
+    assert_eq!(gauntlets[1], Gauntlet { id: 3, level_ids: vec![71, 72] });
+    assert_eq!((gauntlets[0].name(), gauntlets[1].name()), (Some("Fire"), Some("Poison")));
+    assert_eq!(Gauntlet { id: 99, level_ids: vec![] }.name(), None);
+    assert_eq!(gauntlets_to_response(&gauntlets), response);
+    assert!(gauntlets_from_response(&response.replace("71,72", "71,73")).is_err());
+}
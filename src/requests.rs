@@ -0,0 +1,315 @@
+use std::fmt;
+use base64::{Engine, engine::general_purpose::URL_SAFE};
+use crate::{
+    crypto,
+    game_manager::GameManagerDB,
+    interactions::LikedItem
+};
+
+pub const GAME_VERSION: i32 = 22;
+pub const BINARY_VERSION: i32 = 42;
+/// Secret sent with every request
+pub const SECRET: &str = "Wmfd2893gb7";
+
+/// Form-encoded body of a request, fields keep the order they were added in
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Form {
+    fields: Vec<(&'static str, String)>
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn field<T: ToString>(mut self, key: &'static str, value: T) -> Self {
+        self.fields.push((key, value.to_string()));
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter()
+            .find(|(field, _)| *field == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn fields(&self) -> &[(&'static str, String)] {
+        &self.fields
+    }
+
+    /// Fields every request starts with
+    fn game() -> Self {
+        Self::new()
+            .field("gameVersion", GAME_VERSION)
+            .field("binaryVersion", BINARY_VERSION)
+    }
+
+    fn account(self, account: &Account) -> Self {
+        self.field("accountID", account.account_id)
+            .field("gjp2", &account.gjp2)
+    }
+
+    fn device(self, account: &Account) -> Self {
+        self.field("udid", &account.udid)
+            .field("uuid", account.uuid)
+    }
+}
+
+/// `application/x-www-form-urlencoded`
+fn encode_component(f: &mut fmt::Formatter, component: &str) -> fmt::Result {
+    for byte in component.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => write!(f, "{}", byte as char)?,
+            b' ' => f.write_str("+")?,
+            byte => write!(f, "%{byte:02X}")?
+        }
+    }
+    Ok(())
+}
+
+/// Inverse of the encoding of `Form`, which is also how the servers encode
+/// URLs inside responses
+pub(crate) fn decode_component(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes.get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+                continue
+            }
+            (b'+', _) => decoded.push(b' '),
+            (byte, _) => decoded.push(byte)
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+impl fmt::Display for Form {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, (key, value)) in self.fields.iter().enumerate() {
+            if index > 0 { f.write_str("&")? }
+            encode_component(f, key)?;
+            f.write_str("=")?;
+            encode_component(f, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Body of a request to an endpoint, nothing is sent by this crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// File name of the endpoint, e.g. `getGJLevels21.php`
+    pub endpoint: &'static str,
+    pub form: Form
+}
+
+impl Request {
+    /// URL of the endpoint on a server, e.g. `https://www.boomlings.com/database`
+    pub fn url(&self, server: &str) -> String {
+        format!("{}/{}", server.trim_end_matches('/'), self.endpoint)
+    }
+
+    /// Form-encoded body
+    pub fn body(&self) -> String {
+        self.form.to_string()
+    }
+}
+
+/// Logged in account and the device it's used from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub account_id: i32,
+    pub user_name: String,
+    /// `crypto::gjp2` of the password
+    pub gjp2: String,
+    pub udid: String,
+    /// Player ID, unlike what the name suggests
+    pub uuid: i32
+}
+
+impl Account {
+    pub fn new(account_id: i32, user_name: &str, password: &str, udid: &str, uuid: i32) -> Self {
+        Self {
+            account_id,
+            user_name: user_name.to_string(),
+            gjp2: crypto::gjp2(password),
+            udid: udid.to_string(),
+            uuid
+        }
+    }
+
+    /// Account logged in on the save. Saves of 2.2 store the `gjp2` of the
+    /// password, older saves the password itself, which `password` overrides.
+    /// `None` if the save has neither and no password is passed
+    pub fn from_game_manager(game_manager: &GameManagerDB, password: Option<&str>) -> Option<Self> {
+        let gjp2 = match game_manager.account_gjp2() {
+            Some(gjp2) => gjp2.to_string(),
+            None => crypto::gjp2(password.or(game_manager.account_password())?)
+        };
+        Some(Self {
+            account_id: game_manager.account_id()?,
+            user_name: game_manager.account_name().unwrap_or(game_manager.player_name()).to_string(),
+            gjp2,
+            udid: game_manager.player_udid().to_string(),
+            uuid: game_manager.player_user_id()
+        })
+    }
+}
+
+/// Search of getGJLevels21
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LevelSearch {
+    /// Text, level ID or player ID depending on the search type
+    pub query: String,
+    /// 0 for searches, 2 for most liked, 6 for featured and so on
+    pub search_type: i32,
+    pub page: i32
+}
+
+pub fn get_levels(search: &LevelSearch) -> Request {
+    Request {
+        endpoint: "getGJLevels21.php",
+        form: Form::game()
+            .field("type", search.search_type)
+            .field("str", &search.query)
+            .field("page", search.page)
+            .field("total", 0)
+            .field("secret", SECRET)
+    }
+}
+
+/// `rs` is the random string the check is salted with, `inc` whether the
+/// download should be counted
+pub fn download_level(level_id: i32, account: Option<&Account>, inc: bool, rs: &str) -> Request {
+    let mut form = Form::game().field("levelID", level_id);
+    let Some(account) = account else {
+        return Request { endpoint: "downloadGJLevel22.php", form: form.field("secret", SECRET) }
+    };
+    let inc = if inc { "1" } else { "0" };
+    let chk = crypto::chk(&[
+        &level_id.to_string(),
+        inc,
+        rs,
+        &account.account_id.to_string(),
+        &account.udid,
+        &account.uuid.to_string()
+    ], crypto::LEVEL_SALT, crypto::LEVEL_KEY);
+    form = form.account(account).device(account)
+        .field("inc", inc)
+        .field("extras", 0)
+        .field("secret", SECRET)
+        .field("rs", rs)
+        .field("chk", chk);
+    Request { endpoint: "downloadGJLevel22.php", form }
+}
+
+/// Level to upload with uploadGJLevel21
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LevelUpload {
+    /// 0 for levels that weren't uploaded yet
+    pub level_id: i32,
+    pub name: String,
+    pub description: String,
+    pub version: i32,
+    pub length: i32,
+    /// Official song, used if `song_id` is 0
+    pub audio_track: i32,
+    pub song_id: i32,
+    pub objects: i32,
+    pub coins: i32,
+    pub requested_stars: i32,
+    pub unlisted: bool,
+    pub ldm: bool,
+    /// 0 for no copying, 1 for free copies
+    pub password: i32,
+    /// ID of the level this is a copy of
+    pub original_id: i32,
+    pub two_player: bool,
+    pub level_string: String
+}
+
+/// `seed` is a random string like `rs` of other requests
+pub fn upload_level(account: &Account, level: &LevelUpload, seed: &str) -> Request {
+    let form = Form::game()
+        .account(account)
+        .field("userName", &account.user_name)
+        .field("levelID", level.level_id)
+        .field("levelName", &level.name)
+        .field("levelDesc", URL_SAFE.encode(&level.description))
+        .field("levelVersion", level.version)
+        .field("levelLength", level.length)
+        .field("audioTrack", level.audio_track)
+        .field("auto", 0)
+        .field("password", level.password)
+        .field("original", level.original_id)
+        .field("twoPlayer", level.two_player as i32)
+        .field("songID", level.song_id)
+        .field("objects", level.objects)
+        .field("coins", level.coins)
+        .field("requestedStars", level.requested_stars)
+        .field("unlisted", level.unlisted as i32)
+        .field("ldm", level.ldm as i32)
+        .field("levelString", &level.level_string)
+        .field("seed2", crypto::level_seed(&level.level_string))
+        .field("seed", seed)
+        .field("secret", SECRET)
+        .device(account);
+    Request { endpoint: "uploadGJLevel21.php", form }
+}
+
+/// Profile of `target_account_id`, as seen by `account` if logged in
+pub fn get_user_info(target_account_id: i32, account: Option<&Account>) -> Request {
+    let mut form = Form::game();
+    if let Some(account) = account {
+        form = form.account(account);
+    }
+    Request {
+        endpoint: "getGJUserInfo20.php",
+        form: form.field("targetAccountID", target_account_id).field("secret", SECRET)
+    }
+}
+
+pub fn get_song_info(song_id: i32) -> Request {
+    Request {
+        endpoint: "getGJSongInfo.php",
+        form: Form::game().field("songID", song_id).field("secret", SECRET)
+    }
+}
+
+/// Like or dislike of a level or comment, `rs` as in `download_level`
+pub fn like_item(account: &Account, item: &LikedItem, rs: &str) -> Request {
+    let item_type = item.item_type.id();
+    let like = item.is_like as i32;
+    let chk = crypto::chk(&[
+        &item.special.to_string(),
+        &item.id.to_string(),
+        &like.to_string(),
+        &item_type.to_string(),
+        rs,
+        &account.account_id.to_string(),
+        &account.udid,
+        &account.uuid.to_string()
+    ], crypto::LIKE_SALT, crypto::LIKE_KEY);
+    let form = Form::game()
+        .account(account)
+        .device(account)
+        .field("itemID", item.id)
+        .field("like", like)
+        .field("type", item_type)
+        .field("secret", SECRET)
+        .field("special", item.special)
+        .field("rs", rs)
+        .field("chk", chk);
+    Request { endpoint: "likeGJItem211.php", form }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,104 @@
+use super::*;
+use crate::{
+    interactions::LikeItemType,
+    serde::from_any_reader
+};
+
+const LEVEL_STRING: &str = concat!(
+    "H4sIAAAAAAAAC6WQwQ3DIAxFF3IlbGNI1FNm6ADeICv08KE",
+    "H4sIAAAAAAAAC6WQwQ3DIAxFF3IlbGNI1FNm6ADeICv08KE",
+    "H4sIAAAAAAAAC6WQwQ3DIAxFF3IlbGNI1FNm6ADeICv08KE"
+);
+
+fn account() -> Account {
+    Account::new(161, "Tester", "hunter2", "S1234", 3935672)
+}
+
+#[test]
+fn hashes_password() {
+    assert_eq!(account().gjp2, "dcc58eb53f0c1604c19240834e7fd12e73f9a9c7");
+}
+
+fn game_manager(account_keys: &str) -> GameManagerDB {
+    let xml = format!(concat!(
+        r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+        "<k>bgVolume</k><r>0.5</r><k>sfxVolume</k><r>1</r><k>playerName</k><s>Player</s>",
+        "<k>playerUserID</k><i>3935672</i><k>playerUDID</k><s>S1234</s><k>playerFrame</k><i>12</i>",
+        "<k>playerShip</k><i>3</i><k>playerBall</k><i>4</i><k>playerBird</k><i>5</i>",
+        "<k>playerDart</k><i>6</i><k>playerRobot</k><i>7</i><k>playerSpider</k><i>8</i>",
+        "<k>playerColor</k><i>9</i><k>playerColor2</k><i>12</i><k>playerStreak</k><i>2</i>",
+        "<k>playerDeathEffect</k><i>1</i><k>playerIconType</k><i>1</i><k>bootups</k><i>3</i>",
+        "<k>binaryVersion</k><i>42</i><k>resolution</k><i>-1</i><k>texQuality</k><i>3</i>",
+        "<k>GJA_001</k><s>Tester</s><k>GJA_003</k><i>161</i>{}",
+        "</dict></plist>"
+    ), account_keys);
+    from_any_reader(xml.as_bytes()).unwrap().t
+}
+
+#[test]
+fn reads_account_from_save() {
+    let stored_gjp2 = game_manager("<k>GJA_005</k><s>dcc58eb53f0c1604c19240834e7fd12e73f9a9c7</s>");
+    assert_eq!(Account::from_game_manager(&stored_gjp2, None), Some(account()));
+    assert_eq!(Account::from_game_manager(&stored_gjp2, Some("other")), Some(account()));
+
+    let stored_password = game_manager("<k>GJA_002</k><s>hunter2</s>");
+    assert_eq!(Account::from_game_manager(&stored_password, None), Some(account()));
+
+    let neither = game_manager("");
+    assert_eq!(Account::from_game_manager(&neither, None), None);
+    assert_eq!(Account::from_game_manager(&neither, Some("hunter2")), Some(account()));
+}
+
+#[test]
+fn builds_search() {
+    let request = get_levels(&LevelSearch { query: String::from("bloodbath & co"), search_type: 0, page: 2 });
+    assert_eq!(request.url("https://www.boomlings.com/database/"), "https://www.boomlings.com/database/getGJLevels21.php");
+    assert_eq!(
+        request.body(),
+        "gameVersion=22&binaryVersion=42&type=0&str=bloodbath+%26+co&page=2&total=0&secret=Wmfd2893gb7"
+    );
+}
+
+#[test]
+fn builds_download_with_chk() {
+    let request = download_level(128, Some(&account()), true, "ABCDEFGHIJ");
+    assert_eq!(request.form.get("chk"), Some("UAgEDlYGCQUHAQ0EAFRRUVcCBA1RCFBRAAEHBQ5WBgFTUgIEBgRWAg=="));
+    assert_eq!(request.form.get("uuid"), Some("3935672"));
+
+    let anonymous = download_level(128, None, true, "ABCDEFGHIJ");
+    assert_eq!(anonymous.body(), "gameVersion=22&binaryVersion=42&levelID=128&secret=Wmfd2893gb7");
+}
+
+#[test]
+fn builds_like_with_chk() {
+    let item = LikedItem { item_type: LikeItemType::Level, id: 128, is_like: true, special: 0 };
+    let request = like_item(&account(), &item, "ABCDEFGHIJ");
+    assert_eq!(request.endpoint, "likeGJItem211.php");
+    assert_eq!(request.form.get("chk"), Some("AwtUXAkMAFcJBwdcBFsBAAsBWVRRDwFbAANeBV4AUwkGXVMCC1QAUg=="));
+    assert!(request.body().contains("chk=AwtUXAkMAFcJBwdcBFsBAAsBWVRRDwFbAANeBV4AUwkGXVMCC1QAUg%3D%3D"));
+}
+
+#[test]
+fn builds_upload_with_seed2() {
+    let level = LevelUpload { name: String::from("Test"), level_string: LEVEL_STRING.to_string(), ..LevelUpload::default() };
+    let request = upload_level(&account(), &level, "ABCDEFGHIJ");
+    assert_eq!(request.form.get("seed2"), Some("AgFTBQJVUwIBAwYEUQICUgALBgVRAAdWAQQHUAcGDVdTAVcFV1FVBA=="));
+    assert_eq!(request.form.get("userName"), Some("Tester"));
+
+    let short = LevelUpload { level_string: String::from("H4sI"), ..level };
+    let request = upload_level(&account(), &short, "ABCDEFGHIJ");
+    assert_eq!(request.form.get("seed2"), Some("BAVXVgcHVAEHBFYDCgNVVwUHA1ACVwAAVlYHBAJQUAgEVgJXVAZWAA=="));
+}
+
+#[test]
+fn builds_user_info() {
+    let request = get_user_info(71, Some(&account()));
+    assert_eq!(request.form.get("targetAccountID"), Some("71"));
+    assert_eq!(request.form.get("accountID"), Some("161"));
+    assert_eq!(get_user_info(71, None).form.get("gjp2"), None);
+}
+
+#[test]
+fn builds_song_info() {
+    assert_eq!(get_song_info(803223).body(), "gameVersion=22&binaryVersion=42&songID=803223&secret=Wmfd2893gb7");
+}
@@ -0,0 +1,149 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf}
+};
+
+/// Environment variable overriding the save directory lookup
+pub const SAVE_DIR_ENV: &str = "GD_SAVE_DIR";
+
+const STEAM_APP_ID: &str = "322170";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SaveFile {
+    GameManager,
+    LocalLevels,
+    GameManagerBackup,
+    LocalLevelsBackup
+}
+
+impl SaveFile {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::GameManager => "CCGameManager.dat",
+            Self::LocalLevels => "CCLocalLevels.dat",
+            Self::GameManagerBackup => "CCGameManager2.dat",
+            Self::LocalLevelsBackup => "CCLocalLevels2.dat"
+        }
+    }
+}
+
+/// Directory containing the save files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveLocation {
+    dir: PathBuf
+}
+
+impl SaveLocation {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// First existing directory of `candidates()`. The `GD_SAVE_DIR`
+    /// override is returned even if it doesn't exist yet
+    pub fn find() -> Option<Self> {
+        if let Some(dir) = env::var_os(SAVE_DIR_ENV) {
+            return Some(Self::new(dir));
+        }
+        candidates().into_iter()
+            .find(|dir| dir.is_dir())
+            .map(Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, file: SaveFile) -> PathBuf {
+        self.dir.join(file.file_name())
+    }
+
+    pub fn game_manager(&self) -> PathBuf {
+        self.path(SaveFile::GameManager)
+    }
+
+    pub fn local_levels(&self) -> PathBuf {
+        self.path(SaveFile::LocalLevels)
+    }
+
+    pub fn game_manager_backup(&self) -> PathBuf {
+        self.path(SaveFile::GameManagerBackup)
+    }
+
+    pub fn local_levels_backup(&self) -> PathBuf {
+        self.path(SaveFile::LocalLevelsBackup)
+    }
+}
+
+/// Directories the game may store its saves in, in the order they are checked.
+/// Steam libraries listed in `steamapps/libraryfolders.vdf` of the default
+/// Steam installs are checked too
+pub fn candidates() -> Vec<PathBuf> {
+    candidates_from(|name| env::var_os(name), |path| fs::read_to_string(path).ok())
+}
+
+/// `path` values of a `libraryfolders.vdf`, the root of every Steam library
+fn library_folders(vdf: &str) -> Vec<PathBuf> {
+    vdf.lines()
+        .filter_map(|line| {
+            let mut tokens = line.split('"').skip(1).step_by(2);
+            match (tokens.next(), tokens.next()) {
+                (Some("path"), Some(path)) => Some(PathBuf::from(path.replace("\\\\", "\\"))),
+                _ => None
+            }
+        })
+        .collect()
+}
+
+fn candidates_from<F, R>(var: F, read: R) -> Vec<PathBuf>
+where F: Fn(&str) -> Option<OsString>, R: Fn(&Path) -> Option<String> {
+    let mut dirs = vec![];
+
+    if let Some(dir) = var(SAVE_DIR_ENV) {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(local_app_data) = var("LOCALAPPDATA") {
+        dirs.push(PathBuf::from(local_app_data).join("GeometryDash"));
+    }
+
+    let Some(home) = var("HOME").map(PathBuf::from) else { return dirs };
+
+    let mut steam_roots = vec![
+        home.join(".steam/steam"),
+        home.join(".local/share/Steam"),
+        home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam")
+    ];
+    let libraries: Vec<PathBuf> = steam_roots.iter()
+        .filter_map(|root| read(&root.join("steamapps/libraryfolders.vdf")))
+        .flat_map(|vdf| library_folders(&vdf))
+        .collect();
+    for library in libraries {
+        if !steam_roots.contains(&library) {
+            steam_roots.push(library);
+        }
+    }
+    for root in steam_roots {
+        dirs.push(
+            root.join("steamapps/compatdata")
+                .join(STEAM_APP_ID)
+                .join("pfx/drive_c/users/steamuser/AppData/Local/GeometryDash")
+        );
+    }
+
+    let wine_prefix = var("WINEPREFIX")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".wine"));
+    let user = var("USER").unwrap_or_else(|| OsString::from("steamuser"));
+    dirs.push(
+        wine_prefix.join("drive_c/users")
+            .join(user)
+            .join("AppData/Local/GeometryDash")
+    );
+
+    dirs.push(home.join("Library/Application Support/GeometryDash"));
+    dirs
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,53 @@
+use super::*;
+
+#[test]
+fn lists_candidates_in_order() {
+    let dirs = candidates_from(|name| match name {
+        "GD_SAVE_DIR" => Some(OsString::from("/saves")),
+        "HOME" => Some(OsString::from("/home/tester")),
+        "USER" => Some(OsString::from("tester")),
+        _ => None
+    }, |_| None);
+    assert_eq!(dirs, vec![
+        PathBuf::from("/saves"),
+        PathBuf::from("/home/tester/.steam/steam/steamapps/compatdata/322170/pfx/drive_c/users/steamuser/AppData/Local/GeometryDash"),
+        PathBuf::from("/home/tester/.local/share/Steam/steamapps/compatdata/322170/pfx/drive_c/users/steamuser/AppData/Local/GeometryDash"),
+        PathBuf::from("/home/tester/.var/app/com.valvesoftware.Steam/.local/share/Steam/steamapps/compatdata/322170/pfx/drive_c/users/steamuser/AppData/Local/GeometryDash"),
+        PathBuf::from("/home/tester/.wine/drive_c/users/tester/AppData/Local/GeometryDash"),
+        PathBuf::from("/home/tester/Library/Application Support/GeometryDash")
+    ]);
+}
+
+#[test]
+fn checks_secondary_steam_libraries() {
+    let vdf = concat!(
+        "\"libraryfolders\"\n{\n",
+        "\t\"0\"\n\t{\n\t\t\"path\"\t\t\"/home/tester/.local/share/Steam\"\n",
+        "\t\t\"apps\"\n\t\t{\n\t\t\t\"228980\"\t\t\"1\"\n\t\t}\n\t}\n",
+        "\t\"1\"\n\t{\n\t\t\"path\"\t\t\"/mnt/games/SteamLibrary\"\n",
+        "\t\t\"apps\"\n\t\t{\n\t\t\t\"322170\"\t\t\"1\"\n\t\t}\n\t}\n}\n"
+    );
+    let dirs = candidates_from(|name| match name {
+        "HOME" => Some(OsString::from("/home/tester")),
+        _ => None
+    }, |path| (path == Path::new("/home/tester/.local/share/Steam/steamapps/libraryfolders.vdf")).then(|| vdf.to_string()));
+    assert_eq!(dirs[3], PathBuf::from(
+        "/mnt/games/SteamLibrary/steamapps/compatdata/322170/pfx/drive_c/users/steamuser/AppData/Local/GeometryDash"
+    ));
+    assert_eq!(dirs.len(), 6);
+}
+
+#[test]
+fn parses_escaped_library_paths() {
+    let vdf = "\t\t\"path\"\t\t\"D:\\\\SteamLibrary\"\n\t\t\"label\"\t\t\"\"";
+    assert_eq!(library_folders(vdf), [PathBuf::from("D:\\SteamLibrary")]);
+}
+
+#[test]
+fn builds_save_file_paths() {
+    let location = SaveLocation::new("/saves");
+    assert_eq!(location.game_manager(), PathBuf::from("/saves/CCGameManager.dat"));
+    assert_eq!(location.local_levels(), PathBuf::from("/saves/CCLocalLevels.dat"));
+    assert_eq!(location.game_manager_backup(), PathBuf::from("/saves/CCGameManager2.dat"));
+    assert_eq!(location.local_levels_backup(), PathBuf::from("/saves/CCLocalLevels2.dat"));
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+use crate::{
+    game_manager::GameManagerDB,
+    local_levels::LocalLevelsDB,
+    save_location::SaveLocation,
+    save_writer::{SaveWriter, SaveResult},
+    serde::{
+        self as gd_serde,
+        DataWithHeader,
+        error::DeResult
+    }
+};
+
+/// CCGameManager.dat and CCLocalLevels.dat of one save directory
+///
+/// Mutable access marks the file as changed, `save` only writes changed files
+#[derive(Debug)]
+pub struct SaveProfile {
+    location: SaveLocation,
+    game_manager: DataWithHeader<GameManagerDB>,
+    local_levels: DataWithHeader<LocalLevelsDB>,
+    game_manager_backup: Option<DataWithHeader<GameManagerDB>>,
+    local_levels_backup: Option<DataWithHeader<LocalLevelsDB>>,
+    is_game_manager_dirty: bool,
+    is_local_levels_dirty: bool,
+    backups: Option<usize>
+}
+
+impl SaveProfile {
+    /// Loads both saves. The `*2.dat` backups are loaded too if they exist
+    /// and parse, a broken backup doesn't prevent loading the profile
+    pub fn load(location: SaveLocation) -> DeResult<Self> {
+        let game_manager = gd_serde::from_file(location.game_manager())?;
+        let local_levels = gd_serde::from_file(location.local_levels())?;
+        let game_manager_backup = load_backup(location.game_manager_backup());
+        let local_levels_backup = load_backup(location.local_levels_backup());
+        Ok(Self {
+            location,
+            game_manager,
+            local_levels,
+            game_manager_backup,
+            local_levels_backup,
+            is_game_manager_dirty: false,
+            is_local_levels_dirty: false,
+            backups: None
+        })
+    }
+
+    pub fn open<P: AsRef<Path>>(dir: P) -> DeResult<Self> {
+        Self::load(SaveLocation::new(dir.as_ref()))
+    }
+
+    /// Amount of timestamped backups `save` keeps, see `SaveWriter::backups`
+    pub fn backups(mut self, backups: usize) -> Self {
+        self.backups = Some(backups);
+        self
+    }
+
+    pub fn location(&self) -> &SaveLocation {
+        &self.location
+    }
+
+    pub fn game_manager(&self) -> &GameManagerDB {
+        &self.game_manager.t
+    }
+
+    pub fn game_manager_mut(&mut self) -> &mut GameManagerDB {
+        self.is_game_manager_dirty = true;
+        &mut self.game_manager.t
+    }
+
+    pub fn local_levels(&self) -> &LocalLevelsDB {
+        &self.local_levels.t
+    }
+
+    pub fn local_levels_mut(&mut self) -> &mut LocalLevelsDB {
+        self.is_local_levels_dirty = true;
+        &mut self.local_levels.t
+    }
+
+    /// Content of CCGameManager2.dat
+    pub fn game_manager_backup(&self) -> Option<&GameManagerDB> {
+        self.game_manager_backup.as_ref().map(|data| &data.t)
+    }
+
+    /// Content of CCLocalLevels2.dat
+    pub fn local_levels_backup(&self) -> Option<&LocalLevelsDB> {
+        self.local_levels_backup.as_ref().map(|data| &data.t)
+    }
+
+    pub fn is_game_manager_dirty(&self) -> bool {
+        self.is_game_manager_dirty
+    }
+
+    pub fn is_local_levels_dirty(&self) -> bool {
+        self.is_local_levels_dirty
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.is_game_manager_dirty || self.is_local_levels_dirty
+    }
+
+    fn writer(&self, path: PathBuf) -> SaveWriter {
+        let writer = SaveWriter::new(path);
+        match self.backups {
+            Some(backups) => writer.backups(backups),
+            None => writer
+        }
+    }
+
+    /// Writes the saves changed since loading or the last `save`
+    pub fn save(&mut self) -> SaveResult<()> {
+        if self.is_game_manager_dirty {
+            self.writer(self.location.game_manager()).write(&self.game_manager)?;
+            self.is_game_manager_dirty = false;
+        }
+        if self.is_local_levels_dirty {
+            self.writer(self.location.local_levels()).write(&self.local_levels)?;
+            self.is_local_levels_dirty = false;
+        }
+        Ok(())
+    }
+}
+
+fn load_backup<T>(path: PathBuf) -> Option<DataWithHeader<T>>
+where T: serde::de::DeserializeOwned {
+    if path.exists() { gd_serde::from_file(path).ok() } else { None }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,84 @@
+use super::*;
+use std::fs;
+use crate::serde::{ser, encoding::{self, Layer}, SaveEncoding, Value};
+
+const GAME_MANAGER: &str = concat!(
+    r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+    "<k>bgVolume</k><r>0.5</r><k>sfxVolume</k><r>1</r><k>playerName</k><s>Tester</s>",
+    "<k>playerUserID</k><i>77</i><k>playerFrame</k><i>12</i><k>playerShip</k><i>3</i>",
+    "<k>playerBall</k><i>4</i><k>playerBird</k><i>5</i><k>playerDart</k><i>6</i>",
+    "<k>playerRobot</k><i>7</i><k>playerSpider</k><i>8</i><k>playerColor</k><i>9</i>",
+    "<k>playerColor2</k><i>12</i><k>playerStreak</k><i>2</i><k>playerDeathEffect</k><i>1</i>",
+    "<k>playerIconType</k><i>1</i><k>bootups</k><i>3</i><k>binaryVersion</k><i>35</i>",
+    "<k>resolution</k><i>-1</i><k>texQuality</k><i>3</i><k>unmodeled</k><s>kept</s>",
+    "</dict></plist>"
+);
+
+const LOCAL_LEVELS: &str = concat!(
+    r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+    "<k>LLM_01</k><d><k>_isArr</k><t/>",
+    "<k>k_0</k><d><k>k2</k><s>First</s><k>k5</k><s>Tester</s><k>k4</k><s>H4sIAAAA</s></d>",
+    "</d><k>LLM_02</k><i>35</i>",
+    "</dict></plist>"
+);
+
+#[test]
+fn saves_only_changed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let location = SaveLocation::new(dir.path());
+    fs::write(location.game_manager(), ser::encode(GAME_MANAGER.as_bytes()).unwrap()).unwrap();
+    fs::write(location.local_levels(), ser::encode(LOCAL_LEVELS.as_bytes()).unwrap()).unwrap();
+    let game_manager_before = fs::read(location.game_manager()).unwrap();
+
+    let mut profile = SaveProfile::load(location.clone()).unwrap().backups(0);
+    assert!(profile.game_manager_backup().is_none());
+    assert!(!profile.is_dirty());
+    assert_eq!(profile.game_manager().icon_kit().cube, 12);
+
+    profile.local_levels_mut().levels_mut().pop();
+    assert!(profile.is_local_levels_dirty());
+    assert!(!profile.is_game_manager_dirty());
+    profile.save().unwrap();
+    assert!(!profile.is_dirty());
+
+    assert_eq!(fs::read(location.game_manager()).unwrap(), game_manager_before);
+    let reloaded = SaveProfile::load(location).unwrap();
+    assert!(reloaded.local_levels().levels().is_empty());
+}
+
+#[test]
+fn keeps_unmodeled_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let location = SaveLocation::new(dir.path());
+    fs::write(location.game_manager(), ser::encode(GAME_MANAGER.as_bytes()).unwrap()).unwrap();
+    fs::write(location.local_levels(), ser::encode(LOCAL_LEVELS.as_bytes()).unwrap()).unwrap();
+
+    let mut profile = SaveProfile::load(location.clone()).unwrap().backups(0);
+    profile.game_manager_mut();
+    profile.local_levels_mut();
+    profile.save().unwrap();
+
+    let game_manager: DataWithHeader<Value> = gd_serde::from_file(location.game_manager()).unwrap();
+    assert_eq!(game_manager.t.get("unmodeled"), Some(&Value::String(String::from("kept"))));
+    let local_levels: DataWithHeader<Value> = gd_serde::from_file(location.local_levels()).unwrap();
+    let level = &local_levels.t.get("LLM_01").unwrap().as_array().unwrap()[0];
+    assert_eq!(level.get("k4"), Some(&Value::String(String::from("H4sIAAAA"))));
+}
+
+#[test]
+fn saves_mac_saves_encrypted() {
+    let dir = tempfile::tempdir().unwrap();
+    let location = SaveLocation::new(dir.path());
+    fs::write(location.game_manager(), SaveEncoding::Mac.encode(GAME_MANAGER.as_bytes()).unwrap()).unwrap();
+    fs::write(location.local_levels(), SaveEncoding::Mac.encode(LOCAL_LEVELS.as_bytes()).unwrap()).unwrap();
+
+    let mut profile = SaveProfile::load(location.clone()).unwrap().backups(0);
+    profile.local_levels_mut().levels_mut().pop();
+    profile.save().unwrap();
+
+    let saved = fs::read(location.local_levels()).unwrap();
+    let (layers, _) = encoding::decode(&saved[..]).unwrap();
+    assert_eq!(layers, [Layer::Aes]);
+    let reloaded = SaveProfile::load(location).unwrap();
+    assert!(reloaded.local_levels().levels().is_empty());
+}
@@ -0,0 +1,180 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::Path,
+    thread,
+    time::{Duration, SystemTime}
+};
+use serde::de::DeserializeOwned;
+use crate::{
+    game_manager::GameManagerDB,
+    local_levels::{LocalLevelsDB, Level},
+    save_location::SaveLocation,
+    stats::CompletedLevel,
+    serde as gd_serde
+};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatChange {
+    pub id: i32,
+    pub old: i64,
+    pub new: i64
+}
+
+/// What changed in the saves since the previous poll
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SaveDelta {
+    pub stats: Vec<StatChange>,
+    pub completed_levels: Vec<CompletedLevel>,
+    pub new_local_levels: Vec<Level>
+}
+
+impl SaveDelta {
+    pub fn is_empty(&self) -> bool {
+        self.stats.is_empty() && self.completed_levels.is_empty() && self.new_local_levels.is_empty()
+    }
+}
+
+/// Modification time and length, a rewrite changes at least one of them
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Last parsed state of one save file
+#[derive(Debug)]
+struct Watched<T> {
+    stamp: Stamp,
+    data: Option<T>
+}
+
+impl<T: DeserializeOwned> Watched<T> {
+    fn load(path: &Path) -> Self {
+        let stamp = stamp(path);
+        match gd_serde::from_file(path) {
+            Ok(data) => Self { stamp, data: Some(data.t) },
+            Err(_) => Self { stamp: None, data: None }
+        }
+    }
+
+    /// Newly parsed data if the file was rewritten. The stamp is only kept
+    /// once the file parses, so a save caught mid-write counts as unchanged
+    /// and is retried next poll
+    fn reload(&mut self, path: &Path) -> Option<T> {
+        let stamp = stamp(path);
+        if stamp == self.stamp || stamp.is_none() {
+            return None
+        }
+        let data = gd_serde::from_file(path).ok()?.t;
+        self.stamp = stamp;
+        Some(data)
+    }
+}
+
+/// Polls a save directory and reports what changed whenever the game
+/// rewrites CCGameManager.dat or CCLocalLevels.dat
+///
+/// The first state is read when the watcher is created, so only changes made
+/// after that are reported. A file that doesn't parse, like one the game is
+/// still writing, is skipped until it does
+#[derive(Debug)]
+pub struct SaveWatcher {
+    location: SaveLocation,
+    interval: Duration,
+    game_manager: Watched<GameManagerDB>,
+    local_levels: Watched<LocalLevelsDB>
+}
+
+impl SaveWatcher {
+    pub fn new(location: SaveLocation) -> Self {
+        let game_manager = Watched::load(&location.game_manager());
+        let local_levels = Watched::load(&location.local_levels());
+        Self { location, interval: DEFAULT_INTERVAL, game_manager, local_levels }
+    }
+
+    /// Time `wait` sleeps between polls
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn location(&self) -> &SaveLocation {
+        &self.location
+    }
+
+    pub fn game_manager(&self) -> Option<&GameManagerDB> {
+        self.game_manager.data.as_ref()
+    }
+
+    pub fn local_levels(&self) -> Option<&LocalLevelsDB> {
+        self.local_levels.data.as_ref()
+    }
+
+    /// Checks the saves once, `None` if nothing relevant changed
+    pub fn poll(&mut self) -> Option<SaveDelta> {
+        let mut delta = SaveDelta::default();
+
+        if let Some(new) = self.game_manager.reload(&self.location.game_manager()) {
+            if let Some(old) = &self.game_manager.data {
+                delta.stats = stat_changes(old, &new);
+                delta.completed_levels = new.completed_levels()
+                    .filter(|level| !old.has_completed(*level))
+                    .collect();
+                delta.completed_levels.sort_by_key(|level| (level.kind.key_prefix(), level.id));
+            }
+            self.game_manager.data = Some(new);
+        }
+
+        if let Some(new) = self.local_levels.reload(&self.location.local_levels()) {
+            if let Some(old) = &self.local_levels.data {
+                let known: HashSet<_> = old.levels().iter().map(Level::identity).collect();
+                delta.new_local_levels = new.levels().iter()
+                    .filter(|level| !known.contains(&level.identity()))
+                    .cloned()
+                    .collect();
+            }
+            self.local_levels.data = Some(new);
+        }
+
+        if delta.is_empty() { None } else { Some(delta) }
+    }
+
+    /// Blocks until something changes
+    pub fn wait(&mut self) -> SaveDelta {
+        loop {
+            if let Some(delta) = self.poll() {
+                return delta
+            }
+            thread::sleep(self.interval);
+        }
+    }
+}
+
+impl Iterator for SaveWatcher {
+    type Item = SaveDelta;
+
+    /// Never ends
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.wait())
+    }
+}
+
+fn stat_changes(old: &GameManagerDB, new: &GameManagerDB) -> Vec<StatChange> {
+    let mut changes: Vec<_> = new.stats().keys()
+        .chain(old.stats().keys())
+        .filter_map(|id| id.parse().ok())
+        .collect::<HashSet<i32>>()
+        .into_iter()
+        .map(|id| StatChange { id, old: old.stat(id), new: new.stat(id) })
+        .filter(|change| change.old != change.new)
+        .collect();
+    changes.sort_by_key(|change| change.id);
+    changes
+}
+
+#[cfg(test)]
+mod tests;
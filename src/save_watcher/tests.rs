@@ -0,0 +1,89 @@
+use super::*;
+use std::{fs::File, path::PathBuf, time::Duration};
+use crate::{serde::ser, stats::CompletedLevelKind};
+
+fn game_manager(stats: &str, completed: &str) -> String {
+    format!(concat!(
+        r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+        "<k>bgVolume</k><r>0.5</r><k>sfxVolume</k><r>1</r><k>playerName</k><s>Tester</s>",
+        "<k>playerUserID</k><i>77</i><k>playerFrame</k><i>12</i><k>playerShip</k><i>3</i>",
+        "<k>playerBall</k><i>4</i><k>playerBird</k><i>5</i><k>playerDart</k><i>6</i>",
+        "<k>playerRobot</k><i>7</i><k>playerSpider</k><i>8</i><k>playerColor</k><i>9</i>",
+        "<k>playerColor2</k><i>12</i><k>playerStreak</k><i>2</i><k>playerDeathEffect</k><i>1</i>",
+        "<k>playerIconType</k><i>1</i><k>bootups</k><i>3</i><k>binaryVersion</k><i>35</i>",
+        "<k>resolution</k><i>-1</i><k>texQuality</k><i>3</i>",
+        "<k>GS_value</k><d>{}</d><k>GS_completed</k><d>{}</d>",
+        "</dict></plist>"
+    ), stats, completed)
+}
+
+fn local_levels(names: &[&str]) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict><k>LLM_01</k><d><k>_isArr</k><t/>"#);
+    for (index, name) in names.iter().enumerate() {
+        xml += &format!("<k>k_{index}</k><d><k>k2</k><s>{name}</s><k>k5</k><s>Tester</s></d>");
+    }
+    xml + "</d><k>LLM_02</k><i>35</i></dict></plist>"
+}
+
+/// Writes the file and moves its modification time forward, since a rewrite
+/// within the same timestamp tick with the same length would go unnoticed
+fn write(path: PathBuf, xml: &str, age: u64) {
+    fs::write(&path, ser::encode(xml.as_bytes()).unwrap()).unwrap();
+    let modified = SystemTime::now() + Duration::from_secs(age);
+    File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+}
+
+
+#[test]
+fn reports_new_stats_completions_and_levels() {
+    let dir = tempfile::tempdir().unwrap();
+    let location = SaveLocation::new(dir.path());
+    write(location.game_manager(), &game_manager("<k>6</k><s>1204</s>", "<k>c_100</k><s>1</s>"), 0);
+    write(location.local_levels(), &local_levels(&["First"]), 0);
+
+    let mut watcher = SaveWatcher::new(location.clone());
+    assert_eq!(watcher.poll(), None);
+
+    write(
+        location.game_manager(),
+        &game_manager("<k>6</k><s>1210</s><k>1</k><s>5</s>", "<k>c_100</k><s>1</s><k>c_200</k><s>1</s><k>n_3</k><s>1</s><k>star_1</k><s>1</s>"),
+        1
+    );
+    write(location.local_levels(), &local_levels(&["First", "Second"]), 1);
+    let delta = watcher.poll().unwrap();
+    assert_eq!(delta.stats, [
+        StatChange { id: 1, old: 0, new: 5 },
+        StatChange { id: 6, old: 1204, new: 1210 }
+    ]);
+    assert_eq!(delta.completed_levels, [
+        CompletedLevel { kind: CompletedLevelKind::Online, id: 200 },
+        CompletedLevel { kind: CompletedLevelKind::Main, id: 3 }
+    ]);
+    assert_eq!(delta.new_local_levels.len(), 1);
+    assert_eq!(delta.new_local_levels[0].name(), "Second");
+
+    assert_eq!(watcher.poll(), None);
+    assert_eq!(watcher.game_manager().unwrap().stat(6), 1210);
+}
+
+#[test]
+fn retries_save_caught_mid_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let location = SaveLocation::new(dir.path());
+    write(location.game_manager(), &game_manager("", ""), 0);
+    write(location.local_levels(), &local_levels(&["First"]), 0);
+    let mut watcher = SaveWatcher::new(location.clone()).interval(Duration::from_millis(1));
+
+    let complete = ser::encode(game_manager("", "<k>d_9</k><s>1</s>").as_bytes()).unwrap();
+    fs::write(location.game_manager(), &complete[..complete.len() / 2]).unwrap();
+    write(location.local_levels(), &local_levels(&["First", "Second"]), 1);
+    // the truncated file doesn't hide the change of the other one
+    let delta = watcher.wait();
+    assert_eq!(delta.new_local_levels.len(), 1);
+    assert!(delta.completed_levels.is_empty());
+    assert_eq!(watcher.poll(), None);
+
+    write(location.game_manager(), &game_manager("", "<k>d_9</k><s>1</s>"), 1);
+    let delta = watcher.wait();
+    assert_eq!(delta.completed_levels, [CompletedLevel { kind: CompletedLevelKind::Daily, id: 9 }]);
+}
@@ -0,0 +1,167 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{Error as IoError, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH}
+};
+use serde::{Serialize, de::DeserializeOwned};
+use crate::serde::{
+    self as gd_serde,
+    DataWithHeader,
+    error::{DeError, SerError}
+};
+
+const DEFAULT_BACKUPS: usize = 3;
+
+/// Tells apart the temporary files of writes running at the same time
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+pub type SaveResult<T> = std::result::Result<T, SaveError>;
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(IoError),
+    Serialization(SerError),
+    /// The encoded file couldn't be read back, the old save is left untouched
+    Verification(DeError)
+}
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {err}"),
+            Self::Serialization(err) => write!(f, "serialization error: {err}"),
+            Self::Verification(err) => write!(f, "written save doesn't parse: {err}")
+        }
+    }
+}
+
+impl Error for SaveError {}
+
+impl From<IoError> for SaveError {
+    fn from(err: IoError) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Replaces a save file without ever leaving a partially written file behind
+///
+/// The data is encoded into a temporary file next to the save in the encoding
+/// it was read from, synced and parsed back before it's renamed over the old
+/// save. Up to `backups` copies of previous saves are kept as
+/// `<file name>.<unix millis>.bak`, the timestamp is moved past the newest
+/// backup if saves land in the same millisecond so that none is overwritten
+#[derive(Debug, Clone)]
+pub struct SaveWriter {
+    path: PathBuf,
+    backups: usize
+}
+
+impl SaveWriter {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into(), backups: DEFAULT_BACKUPS }
+    }
+
+    /// Amount of backups to keep, 0 disables them
+    pub fn backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn file_name(&self) -> String {
+        self.path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn dir(&self) -> PathBuf {
+        match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from(".")
+        }
+    }
+
+    /// `.<file name>.<pid>.<counter>.tmp`, unique for every write so that
+    /// concurrent writers don't replace each other's temporary file
+    fn temp_path(&self) -> PathBuf {
+        let counter = TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed);
+        self.dir().join(format!(".{}.{}.{counter}.tmp", self.file_name(), process::id()))
+    }
+
+    pub fn write<T>(&self, data: &DataWithHeader<T>) -> SaveResult<()>
+    where T: Serialize + DeserializeOwned {
+        let encoded = gd_serde::ser::to_vec_with_encoding(data, data.encoding).map_err(SaveError::Serialization)?;
+
+        let temp_path = self.temp_path();
+        let written = File::create(&temp_path).and_then(|mut file| {
+            file.write_all(&encoded)?;
+            file.sync_all()
+        });
+        if let Err(err) = written {
+            let _ = fs::remove_file(&temp_path);
+            return Err(SaveError::Io(err));
+        }
+        if let Err(err) = gd_serde::from_file::<T, _>(&temp_path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(SaveError::Verification(err));
+        }
+
+        if self.backups > 0 && self.path.exists() {
+            self.backup()?;
+        }
+        fs::rename(&temp_path, &self.path)?;
+        // makes the rename durable, directories can't be opened on Windows
+        if let Ok(dir) = File::open(self.dir()) {
+            let _ = dir.sync_all();
+        }
+        Ok(())
+    }
+
+    fn backup(&self) -> SaveResult<()> {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|time| time.as_millis())
+            .unwrap_or_default();
+        let mut backups = self.list_backups()?;
+        let timestamp = backups.iter()
+            .map(|(timestamp, _)| timestamp + 1)
+            .fold(millis, u128::max);
+        let path = self.dir().join(format!("{}.{timestamp}.bak", self.file_name()));
+        fs::copy(&self.path, &path)?;
+
+        backups.push((timestamp, path));
+        backups.sort();
+        let excess = backups.len().saturating_sub(self.backups);
+        for (_, path) in backups.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Backups of the save paired with their timestamp, which orders them
+    pub fn list_backups(&self) -> SaveResult<Vec<(u128, PathBuf)>> {
+        let prefix = format!("{}.", self.file_name());
+        let mut backups = vec![];
+        for entry in fs::read_dir(self.dir())? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else { continue };
+            let timestamp = name.strip_prefix(&prefix)
+                .and_then(|name| name.strip_suffix(".bak"))
+                .and_then(|timestamp| timestamp.parse().ok());
+            if let Some(timestamp) = timestamp {
+                backups.push((timestamp, path));
+            }
+        }
+        Ok(backups)
+    }
+}
+
+#[cfg(test)]
+mod tests;
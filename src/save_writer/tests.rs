@@ -0,0 +1,90 @@
+use super::*;
+use serde::Deserialize;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Save {
+    value: i32
+}
+
+fn temp_files(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "tmp"))
+        .collect()
+}
+
+#[test]
+fn writes_and_rotates_backups() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("CCLocalLevels.dat");
+    let writer = SaveWriter::new(&path).backups(2);
+
+    for value in 0..4 {
+        writer.write(&DataWithHeader::new(Save { value })).unwrap();
+    }
+
+    let saved: DataWithHeader<Save> = gd_serde::from_file(&path).unwrap();
+    assert_eq!(saved.t, Save { value: 3 });
+    assert!(temp_files(dir.path()).is_empty());
+
+    let mut backups = writer.list_backups().unwrap();
+    backups.sort();
+    let values: Vec<i32> = backups.iter()
+        .map(|(_, path)| gd_serde::from_file::<Save, _>(path).unwrap().t.value)
+        .collect();
+    assert_eq!(values, vec![1, 2]);
+}
+
+#[test]
+fn keeps_backups_of_saves_in_the_same_millisecond() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("CCGameManager.dat");
+    let writer = SaveWriter::new(&path).backups(10);
+    for value in 0..6 {
+        writer.write(&DataWithHeader::new(Save { value })).unwrap();
+    }
+
+    let mut backups = writer.list_backups().unwrap();
+    backups.sort();
+    let values: Vec<i32> = backups.iter()
+        .map(|(_, path)| gd_serde::from_file::<Save, _>(path).unwrap().t.value)
+        .collect();
+    assert_eq!(values, vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn keeps_old_save_if_verification_fails() {
+    // serializes fine but rejects every value when deserialized
+    #[derive(Serialize, Deserialize, Clone)]
+    #[serde(into = "i32", try_from = "i32")]
+    struct Unreadable;
+
+    impl From<Unreadable> for i32 {
+        fn from(_: Unreadable) -> Self { 0 }
+    }
+
+    impl TryFrom<i32> for Unreadable {
+        type Error = &'static str;
+
+        fn try_from(_: i32) -> Result<Self, Self::Error> { Err("unreadable") }
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("CCGameManager.dat");
+    SaveWriter::new(&path).write(&DataWithHeader::new(Save { value: 1 })).unwrap();
+
+    let result = SaveWriter::new(&path).write(&DataWithHeader::new(Unreadable));
+    assert!(matches!(result, Err(SaveError::Verification(_))));
+
+    let saved: DataWithHeader<Save> = gd_serde::from_file(&path).unwrap();
+    assert_eq!(saved.t, Save { value: 1 });
+    assert!(temp_files(dir.path()).is_empty());
+}
+
+#[test]
+fn gives_every_write_its_own_temp_file() {
+    let writer = SaveWriter::new("CCLocalLevels.dat");
+    let (first, second) = (writer.temp_path(), writer.temp_path());
+    assert_ne!(first, second);
+    assert!(first.to_string_lossy().contains(&format!(".CCLocalLevels.dat.{}.", process::id())));
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use crate::{
+    local_levels::Level,
+    songs::SongInfo,
+    serde::{
+        robtop::{self, Separators},
+        error::{DeError, DeResult}
+    }
+};
+
+/// Creator of a level in a search page, stored as `playerID:name:accountID`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Creator {
+    pub player_id: i32,
+    pub name: String,
+    /// 0 for players without an account
+    pub account_id: i32
+}
+
+impl Creator {
+    fn from_response(record: &str) -> DeResult<Self> {
+        let mut fields = record.split(':');
+        let mut next = || fields.next().ok_or(DeError::Deserialization);
+        let player_id = next()?.parse().map_err(|_| DeError::Deserialization)?;
+        let name = next()?.to_string();
+        let account_id = next()?.parse().unwrap_or_default();
+        Ok(Self { player_id, name, account_id })
+    }
+}
+
+/// Pagination of a search page, stored as `total:offset:count`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PageInfo {
+    /// Levels matching the search on every page
+    pub total: i32,
+    /// Amount of levels on the pages before this one
+    pub offset: i32,
+    /// Levels per page
+    pub count: i32
+}
+
+impl PageInfo {
+    pub(crate) fn from_response(section: &str) -> DeResult<Self> {
+        let fields: Vec<i32> = section.split(':')
+            .map(|field| field.parse().map_err(|_| DeError::Deserialization))
+            .collect::<DeResult<_>>()?;
+        match fields[..] {
+            [total, offset, count] => Ok(Self { total, offset, count }),
+            _ => Err(DeError::Deserialization)
+        }
+    }
+
+    /// Index of the page, starting at 0
+    pub fn page(&self) -> i32 {
+        if self.count > 0 { self.offset / self.count } else { 0 }
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.offset + self.count < self.total
+    }
+}
+
+/// getGJLevels21 response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchPage {
+    /// Levels with the creator names filled in from `creators`
+    pub levels: Vec<Level>,
+    pub creators: Vec<Creator>,
+    pub songs: Vec<SongInfo>,
+    pub page: PageInfo
+}
+
+impl SearchPage {
+    /// Reads the `#` separated levels, creators, songs and pagination. The
+    /// `-1` the server answers with when nothing matches is an empty page
+    pub fn from_response(response: &str) -> DeResult<Self> {
+        let response = response.trim();
+        if response == "-1" {
+            return Ok(Self::default())
+        }
+        let mut sections = robtop::sections(response);
+        let mut next = || sections.next().unwrap_or_default();
+
+        let mut levels: Vec<Level> = robtop::from_str(next())?;
+        let creators = next().split('|')
+            .filter(|record| !record.is_empty())
+            .map(Creator::from_response)
+            .collect::<DeResult<Vec<_>>>()?;
+        let songs = robtop::from_str_with(next(), Separators::SONG)?;
+        let page = match next() {
+            "" => PageInfo::default(),
+            page => PageInfo::from_response(page)?
+        };
+
+        let names: HashMap<i32, &str> = creators.iter()
+            .map(|creator| (creator.player_id, creator.name.as_str()))
+            .collect();
+        for level in &mut levels {
+            level.use_save_keys();
+            if let Some(name) = level.player_id().and_then(|id| names.get(&id)) {
+                level.set_creator(name.to_string());
+            }
+        }
+        Ok(Self { levels, creators, songs, page })
+    }
+
+    pub fn creator(&self, player_id: i32) -> Option<&Creator> {
+        self.creators.iter().find(|creator| creator.player_id == player_id)
+    }
+
+    pub fn song(&self, id: i32) -> Option<&SongInfo> {
+        self.songs.iter().find(|song| song.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests;
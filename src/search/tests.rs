@@ -0,0 +1,38 @@
+use super::*;
+
+const RESPONSE: &str = concat!(
+    "1:128:2:First:5:1:6:3935672:8:10:9:30:10:1234:35:803223",
+    "|1:129:2:Second:5:3:6:71:8:10:9:20:10:56:35:0",
+    "|1:130:2:Unlisted creator:6:99",
+    "#3935672:Tester:161|71:RobTop:71",
+    "#1~|~803223~|~2~|~Supernova~|~3~|~12~|~4~|~Xtrullor~|~5~|~9.56~|~6~|~~|~10~|~https%3A%2F%2Fexample.com~|~7~|~~|~8~|~1",
+    "#9999:10:10",
+    "#a1b2c3"
+);
+
+#[test]
+fn parses_search_page() {
+    let page = SearchPage::from_response(RESPONSE).unwrap();
+    assert_eq!(page.levels.len(), 3);
+    assert_eq!(page.levels[0].name(), "First");
+    assert_eq!(page.levels[0].custom_song_id(), Some(803223));
+    assert_eq!(page.creators[1], Creator { player_id: 71, name: String::from("RobTop"), account_id: 71 });
+    assert_eq!(page.song(803223).map(|song| song.artist.as_str()), Some("Xtrullor"));
+    assert_eq!(page.page, PageInfo { total: 9999, offset: 10, count: 10 });
+    assert_eq!(page.page.page(), 1);
+    assert!(page.page.has_next());
+}
+
+#[test]
+fn joins_creator_names() {
+    let page = SearchPage::from_response(RESPONSE).unwrap();
+    let creators: Vec<&str> = page.levels.iter().map(Level::creator).collect();
+    assert_eq!(creators, ["Tester", "RobTop", ""]);
+    assert_eq!(page.creator(3935672).map(|creator| creator.account_id), Some(161));
+}
+
+#[test]
+fn parses_empty_results() {
+    assert_eq!(SearchPage::from_response("-1").unwrap(), SearchPage::default());
+    assert!(SearchPage::from_response("1:128#bad creator#").is_err());
+}
@@ -1,4 +1,47 @@
 pub mod error;
 pub mod ser;
 pub mod de;
-pub use de::{ from_reader, from_file };
+pub mod value;
+pub mod recovery;
+pub mod diff;
+pub mod encoding;
+pub mod robtop;
+pub use de::{ from_reader, from_any_reader, from_file };
+pub use ser::{ to_writer, to_file };
+pub use value::Value;
+pub use diff::diff;
+pub use encoding::SaveEncoding;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    pub xml_version: String,
+    pub plist_version: String,
+    pub gj_version: String
+}
+
+impl Default for Header {
+    /// Header the game writes to its saves
+    fn default() -> Self {
+        Self {
+            xml_version: String::from("1.0"),
+            plist_version: String::from("1.0"),
+            gj_version: String::from("2.0")
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DataWithHeader<T> {
+    pub t: T,
+    pub header: Header,
+    /// Encoding the save was read from, so that it's written back the same way
+    pub encoding: SaveEncoding
+}
+
+impl<T> DataWithHeader<T> {
+    /// Wraps the data with the default header, to be encoded like the game
+    /// does on Windows
+    pub fn new(t: T) -> Self {
+        Self { t, header: Header::default(), encoding: SaveEncoding::Windows }
+    }
+}
@@ -2,74 +2,52 @@ use std::{
     path::Path,
     fs::File,
     io::{Read, BufReader},
+    marker::PhantomData,
     sync::Arc
 };
-use serde::{de, serde_if_integer128};
+use serde::{de, forward_to_deserialize_any};
 
-use xorstream::Transformer as XorReader;
-use base64::{
-    read::DecoderReader as Base64Reader,
-    engine::{GeneralPurpose, general_purpose::URL_SAFE}
-};
-use libflate::gzip::Decoder as GzipReader;
 use quick_xml::{
     Reader as XmlReader,
     Result as XmlResult,
     events::Event as XmlEvent
 };
 
-use super::error::{ DeError, DeResult };
+use super::{
+    encoding::{self, SaveEncoding},
+    error::{ DeError, DeResult }
+};
+pub use super::{ Header, DataWithHeader };
 
-type DecodedDataReader<'de, R> =
-    GzipReader<
-        Base64Reader<
-            'de,
-            GeneralPurpose,
-            XorReader<R>
-        >
-    >;
+/// XML of the save, whichever encoding it was stored in
+type DecodedDataReader<'de> = Box<dyn Read + 'de>;
 
-type DecodedDataXmlReader<'de, R> =
+type DecodedDataXmlReader<'de> =
 XmlReader<
     BufReader<
-        DecodedDataReader<'de, R>
+        DecodedDataReader<'de>
     >
 >;
 
-#[derive(Debug)]
-pub struct Header { // move to serde
-    pub xml_version: String,
-    pub plist_version: String,
-    pub gj_version: String
-}
-
-#[derive(Debug)]
-pub struct DataWithHeader<T> {
-    pub t: T,
-    pub header: Header
-}
-
 pub struct Deserializer<'de, R: Read> {
-    reader: DecodedDataXmlReader<'de, R>,
+    reader: DecodedDataXmlReader<'de>,
     buffer: Vec<u8>,
     header: Header,
+    encoding: SaveEncoding,
     peeked_next: Option<Arc<DeEvent>>,
     is_instant_dict_end: bool,
-    is_eof: bool
+    is_eof: bool,
+    source: PhantomData<R>
 }
 
-impl<'de, R: Read> Deserializer<'de, R> {
-    fn decode(reader: R) -> DeResult<DecodedDataReader<'de, R>> {
-        let reader = XorReader::new(vec![11], reader);
-        let reader = Base64Reader::new(reader, &URL_SAFE);
-        match GzipReader::new(reader) {
-            Ok(reader) => Ok(reader),
-            Err(err) => Err(DeError::Io(err))
-        }
+impl<'de, R: Read + 'de> Deserializer<'de, R> {
+    /// Peels off whichever encoding layers the save has
+    fn decode(reader: R) -> DeResult<(SaveEncoding, DecodedDataReader<'de>)> {
+        encoding::decode(reader).map(|(layers, reader)| (SaveEncoding::from_layers(&layers), reader))
     }
 
     pub fn from_reader(reader: R) -> DeResult<Self> {
-        let reader = Self::decode(reader)?;
+        let (encoding, reader) = Self::decode(reader)?;
         let reader = XmlReader::from_reader(BufReader::new(reader));
         Ok(Self {
             reader,
@@ -79,9 +57,11 @@ impl<'de, R: Read> Deserializer<'de, R> {
                 plist_version: String::new(),
                 gj_version: String::new()
             },
+            encoding,
             peeked_next: None,
             is_instant_dict_end: false,
-            is_eof: false
+            is_eof: false,
+            source: PhantomData
         })
     }
 }
@@ -95,7 +75,9 @@ impl<'de> Deserializer<'de, File> {
     }
 }
 
-pub fn from_reader<'de, T, R: Read>(reader: R) -> DeResult<DataWithHeader<T>>
+/// Deserializes a save in any state of encoding, from a fully encoded `.dat`
+/// over a save missing some of the layers to plain XML, see `encoding::decode`
+pub fn from_any_reader<'de, T, R: Read + 'de>(reader: R) -> DeResult<DataWithHeader<T>>
 where T: de::Deserialize<'de> {
     let mut deserializer = Deserializer::from_reader(reader)?;
     deserializer.skip_header()?;
@@ -104,27 +86,25 @@ where T: de::Deserialize<'de> {
         if let DeEvent::Eof = *event {
             Ok(DataWithHeader {
                 t: result,
-                header: deserializer.header
+                header: deserializer.header,
+                encoding: deserializer.encoding
             })
         } else { Err(DeError::ExpectedEof) }
     }
     else { Err(DeError::ExpectedEof) }
 }
 
+pub fn from_reader<'de, T, R: Read + 'de>(reader: R) -> DeResult<DataWithHeader<T>>
+where T: de::Deserialize<'de> {
+    from_any_reader(reader)
+}
+
 pub fn from_file<'de, T, P: AsRef<Path>>(path: P) -> DeResult<DataWithHeader<T>>
 where T: de::Deserialize<'de> {
-    let mut deserializer = Deserializer::from_file(path)?;
-    deserializer.skip_header()?;
-    let result = T::deserialize(&mut deserializer)?;
-    if let Ok(event) = deserializer.next() {
-        if let DeEvent::Eof = *event {
-            Ok(DataWithHeader {
-                t: result,
-                header: deserializer.header
-            })
-        } else { Err(DeError::ExpectedEof) }
+    match File::open(path) {
+        Ok(file) => from_any_reader(file),
+        Err(err) => Err(DeError::Io(err))
     }
-    else { Err(DeError::ExpectedEof) }
 }
 
 #[derive(PartialEq, Debug)]
@@ -248,13 +228,18 @@ impl<'a, 'de, R: Read> Deserializer<'de, R> {
                             } else { return Err(DeError::UnexpectedXmlTag) }
                         }
                         XmlEvent::End(tag) => {
-                            if let DeEventExpected::None = expected {
-                                match tag.name().into_inner() {
+                            match expected {
+                                DeEventExpected::None => match tag.name().into_inner() {
                                     b"plist" | b"k" | b"s" | b"i" | b"r" | b"t" => {}
                                     b"d" | b"dict" => { save_next_peek!(self, DeEvent::DictEnd) }
                                     _ => { return Err(DeError::UnknownXmlTag) }
                                 }
-                            } else { return Err(DeError::UnexpectedXmlTag) }
+                                // empty element such as `<s></s>`
+                                DeEventExpected::Key => { save_next_peek!(self, DeEvent::Key(String::new())) }
+                                DeEventExpected::String => { save_next_peek!(self, DeEvent::String(String::new())) }
+                                DeEventExpected::Integer => { save_next_peek!(self, DeEvent::Integer(String::new())) }
+                                DeEventExpected::Real => { save_next_peek!(self, DeEvent::Real(String::new())) }
+                            }
                         }
                         XmlEvent::Empty(tag) => {
                             if let DeEventExpected::None = expected {
@@ -303,8 +288,8 @@ impl<'a, 'de, R: Read> Deserializer<'de, R> {
         }
     }
 
-    fn peek(&'a mut self) -> DeResult<&DeEvent> {
-        if let None = self.peeked_next {
+    fn peek(&'a mut self) -> DeResult<&'a DeEvent> {
+        if self.peeked_next.is_none() {
             self.save_next_peek()?;
         }
         if let Some(peeked) = &self.peeked_next {
@@ -313,11 +298,11 @@ impl<'a, 'de, R: Read> Deserializer<'de, R> {
     }
     
     fn next(&'a mut self) -> DeResult<Arc<DeEvent>> {
-        if let None = self.peeked_next {
+        if self.peeked_next.is_none() {
             self.save_next_peek()?;
         }
         if let Some(peeked) = &self.peeked_next {
-            let peeked = Arc::clone(&peeked);
+            let peeked = Arc::clone(peeked);
             self.peeked_next = None;
             Ok(peeked)
         } else { unreachable!() }
@@ -380,12 +365,14 @@ impl<'a, 'de, R: Read> de::MapAccess<'de> for DictReader<'a, 'de, R> {
 
 struct ArrayReader<'a, 'de, R: Read> {
     de: &'a mut Deserializer<'de, R>,
-    cur_index: usize
+    cur_index: usize,
+    /// Index of the key already read while elements before it were missing
+    next_index: Option<usize>
 }
 
 impl<'a, 'de, R: Read> ArrayReader<'a, 'de, R> {
     fn new(de: &'a mut Deserializer<'de, R>) -> Self {
-        Self { de, cur_index: 0 }
+        Self { de, cur_index: 0, next_index: None }
     }
 }
 
@@ -394,26 +381,66 @@ impl<'a, 'de, R: Read> de::SeqAccess<'de> for ArrayReader<'a, 'de, R> {
 
     fn next_element_seed<T>(&mut self, seed: T) -> DeResult<Option<T::Value>>
     where T: de::DeserializeSeed<'de> {
-        match &*self.de.next()? {
-            DeEvent::DictEnd => Ok(None),
-            DeEvent::Key(key) => {
-                if *key == String::from("k_") + &self.cur_index.to_string() {
-                    match self.de.peek()? {
-                        DeEvent::DictStart |
-                        DeEvent::String(_) |
-                        DeEvent::Integer(_) |
-                        DeEvent::Real(_) |
-                        DeEvent::True => {self.cur_index+=1;Ok(Some(seed.deserialize(&mut *self.de)?))},
-                        _ => Err(DeError::Deserialization)
-                    }
-                } else { Err(DeError::Deserialization) }
+        let index = match self.next_index.take() {
+            Some(index) => index,
+            None => match &*self.de.next()? {
+                DeEvent::DictEnd => return Ok(None),
+                DeEvent::Key(key) => key.strip_prefix("k_")
+                    .and_then(|index| index.parse().ok())
+                    .ok_or(DeError::Deserialization)?,
+                _ => return Err(DeError::Deserialization)
             }
+        };
+        if index < self.cur_index {
+            return Err(DeError::Deserialization)
+        }
+        self.cur_index += 1;
+        // elements that are omitted from the save, see `MissingElement`
+        if index >= self.cur_index {
+            self.next_index = Some(index);
+            return seed.deserialize(MissingElement).map(Some)
+        }
+        match self.de.peek()? {
+            DeEvent::DictStart |
+            DeEvent::String(_) |
+            DeEvent::Integer(_) |
+            DeEvent::Real(_) |
+            DeEvent::True => Ok(Some(seed.deserialize(&mut *self.de)?)),
             _ => Err(DeError::Deserialization)
         }
     }
 }
 
-impl<'de, 'a, R: Read> Deserializer<'de, R> {
+/// Array element without a `k_<index>` key, which is how `None` and `false`
+/// are stored
+struct MissingElement;
+
+impl<'de> de::Deserializer<'de> for MissingElement {
+    type Error = DeError;
+
+    fn deserialize_any<V>(self, _visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        Err(DeError::Deserialization)
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_none()
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+        byte_buf unit unit_struct newtype_struct seq tuple tuple_struct map
+        struct enum identifier ignored_any
+    }
+}
+
+impl<'de, R: Read> Deserializer<'de, R> {
     fn skip_header(&mut self) -> DeResult<()> {
         if let DeEvent::XmlVersion(xml_version) = &*self.next()? {
             self.header.xml_version = xml_version.to_string();
@@ -431,13 +458,13 @@ impl<'de, 'a, R: Read> Deserializer<'de, R> {
 
     fn deserialize_map_content<V>(&mut self, visitor: V) -> DeResult<V::Value>
     where V: de::Visitor<'de> {
-        let map = visitor.visit_map(DictReader::new(self));
-        if let DeEvent::DictEnd = *self.next().unwrap_or_else(|_| unreachable!()) { map }
+        let map = visitor.visit_map(DictReader::new(self))?;
+        if let DeEvent::DictEnd = *self.next().unwrap_or_else(|_| unreachable!()) { Ok(map) }
         else { unreachable!() }
     }
 }
 
-impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<'de, R> {
+impl<'de, R: Read> de::Deserializer<'de> for &mut Deserializer<'de, R> {
     type Error = DeError;
 
     fn deserialize_any<V>(self, visitor: V) -> DeResult<V::Value>
@@ -464,8 +491,8 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<'de, R> {
             }
             DeEvent::String(_) => self.deserialize_str(visitor),
             DeEvent::Key(_) => self.deserialize_str(visitor),
-            DeEvent::Integer(_) => self.deserialize_i32(visitor),
-            DeEvent::Real(_) => self.deserialize_f32(visitor),
+            DeEvent::Integer(_) => self.deserialize_i64(visitor),
+            DeEvent::Real(_) => self.deserialize_f64(visitor),
             DeEvent::True => self.deserialize_bool(visitor),
             _ => Err(DeError::Deserialization)
         }
@@ -490,10 +517,8 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<'de, R> {
     deserialize_type!(deserialize_u32 => visit_u32, 1);
     deserialize_type!(deserialize_u64 => visit_u64, 1);
 
-    serde_if_integer128! {
-        deserialize_type!(deserialize_i128 => visit_i128, 1);
-        deserialize_type!(deserialize_u128 => visit_u128, 1);
-    }
+    deserialize_type!(deserialize_i128 => visit_i128, 1);
+    deserialize_type!(deserialize_u128 => visit_u128, 1);
 
     deserialize_type!(deserialize_f32 => visit_f32, 1.0);
     deserialize_type!(deserialize_f64 => visit_f64, 1.0);
@@ -515,7 +540,17 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<'de, R> {
         }
     }
 
-    deserialize_type!(deserialize_string => visit_string, String::from("true"));
+    fn deserialize_string<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        match &*self.next()? {
+            DeEvent::String(text) |
+            DeEvent::Key(text) |
+            DeEvent::Integer(text) |
+            DeEvent::Real(text) => { visitor.visit_string(text.clone()) }
+            DeEvent::True => { visitor.visit_string(String::from("true")) }
+            _ => Err(DeError::Deserialization)
+        }
+    }
 
     fn deserialize_bytes<V>(self, visitor: V) -> DeResult<V::Value>
     where V: de::Visitor<'de> {
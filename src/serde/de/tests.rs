@@ -112,7 +112,7 @@ fn test_data() -> Vec<TestSample> { vec![
 fn decodes_correctly() {
     for sample in test_data() {
         let mut decoded_by_func = vec![];
-        let mut reader = Deserializer::decode(&sample.encoded[..]).unwrap();
+        let (_, mut reader) = Deserializer::decode(&sample.encoded[..]).unwrap();
         reader.read_to_end(&mut decoded_by_func).unwrap();
         assert_eq!(sample.decoded, decoded_by_func);
     }
@@ -0,0 +1,170 @@
+use std::{collections::HashMap, fmt};
+use serde::Serialize;
+use super::value::Value;
+
+/// Difference between two saves at a key path such as `GS_value.6`,
+/// array elements are addressed by their `k_<index>` key
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Change {
+    Added { path: String, value: Value },
+    Removed { path: String, value: Value },
+    Changed { path: String, old: Value, new: Value },
+    /// Name of a level that was matched by its ID
+    Renamed { path: String, old: Value, new: Value }
+}
+
+impl Change {
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Added { path, .. } |
+            Self::Removed { path, .. } |
+            Self::Changed { path, .. } |
+            Self::Renamed { path, .. } => path
+        }
+    }
+}
+
+/// Strings are shown unquoted since most numbers in saves are stored as strings
+struct Text<'a>(&'a Value);
+
+impl fmt::Display for Text<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Value::String(text) => write!(f, "{text}"),
+            value => write!(f, "{value}")
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Added { path, value } => write!(f, "{path} added: {}", Text(value)),
+            Self::Removed { path, value } => write!(f, "{path} removed: {}", Text(value)),
+            Self::Changed { path, old, new } => write!(f, "{path}: {} -> {}", Text(old), Text(new)),
+            Self::Renamed { path, old, new } => write!(f, "{path} renamed: {} -> {}", Text(old), Text(new))
+        }
+    }
+}
+
+/// Key paths added, removed and changed from `a` to `b`
+///
+/// Arrays of levels are matched by level ID, or by name for levels that were
+/// never uploaded, so reordering levels doesn't show up as changes
+pub fn diff(a: &Value, b: &Value) -> Vec<Change> {
+    let mut changes = vec![];
+    diff_values("", a, b, false, &mut changes);
+    changes
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() { key.to_string() } else { format!("{path}.{key}") }
+}
+
+fn diff_values(path: &str, a: &Value, b: &Value, is_level: bool, changes: &mut Vec<Change>) {
+    match (a, b) {
+        (Value::Dict(a), Value::Dict(b)) => {
+            for (key, old) in a {
+                let key_path = join(path, key);
+                match b.get(key) {
+                    Some(new) if is_level && key == "k2" && old != new => {
+                        changes.push(Change::Renamed { path: key_path, old: old.clone(), new: new.clone() });
+                    }
+                    Some(new) => diff_values(&key_path, old, new, false, changes),
+                    None => changes.push(Change::Removed { path: key_path, value: old.clone() })
+                }
+            }
+            for (key, new) in b {
+                if !a.contains_key(key) {
+                    changes.push(Change::Added { path: join(path, key), value: new.clone() });
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let is_levels = a.iter().chain(b).all(|value| value.get("k2").is_some());
+            let pairs = if is_levels { match_levels(a, b) } else { match_indices(a, b) };
+            for pair in pairs {
+                match pair {
+                    (Some(old), Some(new)) => {
+                        diff_values(&join(path, &format!("k_{new}")), &a[old], &b[new], is_levels, changes);
+                    }
+                    (Some(old), None) => changes.push(Change::Removed {
+                        path: join(path, &format!("k_{old}")),
+                        value: a[old].clone()
+                    }),
+                    (None, Some(new)) => changes.push(Change::Added {
+                        path: join(path, &format!("k_{new}")),
+                        value: b[new].clone()
+                    }),
+                    (None, None) => unreachable!()
+                }
+            }
+        }
+        (a, b) => if a != b {
+            changes.push(Change::Changed { path: path.to_string(), old: a.clone(), new: b.clone() });
+        }
+    }
+}
+
+type IndexPairs = Vec<(Option<usize>, Option<usize>)>;
+
+fn match_indices(a: &[Value], b: &[Value]) -> IndexPairs {
+    (0..a.len().max(b.len()))
+        .map(|index| (Some(index).filter(|_| index < a.len()), Some(index).filter(|_| index < b.len())))
+        .collect()
+}
+
+fn level_id(level: &Value) -> Option<i64> {
+    match level.get("k1") {
+        Some(Value::Integer(id)) if *id > 0 => Some(*id),
+        Some(Value::String(id)) => id.parse().ok().filter(|id| *id > 0),
+        _ => None
+    }
+}
+
+/// Pairs levels with the same ID first, then the remaining ones by name
+fn match_levels(a: &[Value], b: &[Value]) -> IndexPairs {
+    let mut unmatched_b: Vec<Option<usize>> = (0..b.len()).map(Some).collect();
+    let mut pairs: IndexPairs = vec![];
+
+    let mut b_by_id: HashMap<i64, usize> = HashMap::new();
+    for (index, level) in b.iter().enumerate() {
+        if let Some(id) = level_id(level) {
+            b_by_id.entry(id).or_insert(index);
+        }
+    }
+    let mut unmatched_a = vec![];
+    for (index, level) in a.iter().enumerate() {
+        let matched = level_id(level)
+            .and_then(|id| b_by_id.remove(&id))
+            .filter(|new| unmatched_b[*new].is_some());
+        match matched {
+            Some(new) => {
+                unmatched_b[new] = None;
+                pairs.push((Some(index), Some(new)));
+            }
+            None => unmatched_a.push(index)
+        }
+    }
+
+    for index in unmatched_a {
+        let name = a[index].get("k2");
+        let matched = unmatched_b.iter()
+            .flatten()
+            .copied()
+            .find(|new| level_id(&b[*new]).is_none() && b[*new].get("k2") == name);
+        match matched {
+            Some(new) => {
+                unmatched_b[new] = None;
+                pairs.push((Some(index), Some(new)));
+            }
+            None => pairs.push((Some(index), None))
+        }
+    }
+    pairs.extend(unmatched_b.into_iter().flatten().map(|new| (None, Some(new))));
+    pairs
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+use super::*;
+
+fn dict(entries: &[(&str, Value)]) -> Value {
+    Value::Dict(entries.iter().map(|(key, value)| (key.to_string(), value.clone())).collect::<BTreeMap<_, _>>())
+}
+
+fn string(text: &str) -> Value {
+    Value::String(text.to_string())
+}
+
+fn level(id: i64, name: &str) -> Value {
+    dict(&[("k1", Value::Integer(id)), ("k2", string(name)), ("k5", string("Tester"))])
+}
+
+#[test]
+fn reports_changed_stats() {
+    let a = dict(&[("GS_value", dict(&[("6", string("1204")), ("8", string("10"))]))]);
+    let b = dict(&[("GS_value", dict(&[("6", string("1210")), ("9", string("3"))]))]);
+    let changes = diff(&a, &b);
+    assert_eq!(changes.iter().map(ToString::to_string).collect::<Vec<_>>(), [
+        "GS_value.6: 1204 -> 1210",
+        "GS_value.8 removed: 10",
+        "GS_value.9 added: 3"
+    ]);
+}
+
+#[test]
+fn matches_levels_by_id() {
+    let a = dict(&[("LLM_01", Value::Array(vec![level(1, "First"), level(2, "Second")]))]);
+    let b = dict(&[("LLM_01", Value::Array(vec![level(3, "Third"), level(2, "Second"), level(1, "Renamed")]))]);
+    let changes = diff(&a, &b);
+    assert_eq!(changes, [
+        Change::Renamed { path: "LLM_01.k_2.k2".into(), old: string("First"), new: string("Renamed") },
+        Change::Added { path: "LLM_01.k_0".into(), value: level(3, "Third") }
+    ]);
+}
+
+#[test]
+fn matches_unuploaded_levels_by_name() {
+    let a = dict(&[("LLM_01", Value::Array(vec![level(0, "Draft"), level(0, "Old")]))]);
+    let mut edited = level(0, "Draft");
+    if let Value::Dict(dict) = &mut edited {
+        dict.insert("k5".into(), string("Someone"));
+    }
+    let b = dict(&[("LLM_01", Value::Array(vec![edited]))]);
+    assert_eq!(diff(&a, &b), [
+        Change::Changed { path: "LLM_01.k_0.k5".into(), old: string("Tester"), new: string("Someone") },
+        Change::Removed { path: "LLM_01.k_1".into(), value: level(0, "Old") }
+    ]);
+}
+
+#[test]
+fn serializes_to_json() {
+    let change = Change::Changed { path: "GS_value.6".into(), old: string("1204"), new: string("1210") };
+    assert_eq!(
+        serde_json::to_string(&change).unwrap(),
+        r#"{"kind":"changed","path":"GS_value.6","old":"1204","new":"1210"}"#
+    );
+}
@@ -0,0 +1,196 @@
+use std::io::{Cursor, Read};
+
+use aes::{
+    Aes256,
+    cipher::{BlockDecrypt, BlockEncrypt, KeyInit, generic_array::GenericArray}
+};
+use base64::{
+    read::DecoderReader as Base64Reader,
+    engine::general_purpose::URL_SAFE
+};
+use libflate::gzip::Decoder as GzipReader;
+use xorstream::Transformer as XorReader;
+
+use super::{
+    ser,
+    error::{DeError, DeResult, SerResult}
+};
+
+/// Key of the AES-256-ECB encryption of macOS saves
+const MAC_KEY: &[u8; 32] = b"ipu9TUv54yv]isFMh5@;t.5w34E2Ry@{";
+const BLOCK_LEN: usize = 16;
+
+/// Length of the prefix needed to tell the layers apart
+const DETECT_LEN: usize = 4;
+
+/// One transformation applied on top of the XML of a save
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// AES-256-ECB with a static key and PKCS7 padding
+    Aes,
+    /// Every byte XORed with 11
+    Xor,
+    /// URL safe base64
+    Base64,
+    Gzip
+}
+
+impl Layer {
+    /// Layer the data starts with, `None` for XML and unrecognized data
+    pub fn detect(prefix: &[u8]) -> Option<Self> {
+        // "H4sI" is the base64 of the gzip magic, "C?xB" is that XORed with 11
+        if prefix.starts_with(b"C?xB") {
+            Some(Self::Xor)
+        } else if prefix.starts_with(b"H4sI") {
+            Some(Self::Base64)
+        } else if prefix.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else {
+            None
+        }
+    }
+
+    /// Reader of the data under this layer
+    pub fn decode<'a, R: Read + 'a>(self, mut reader: R) -> DeResult<Box<dyn Read + 'a>> {
+        Ok(match self {
+            Self::Aes => {
+                let mut encrypted = vec![];
+                reader.read_to_end(&mut encrypted).map_err(DeError::Io)?;
+                Box::new(Cursor::new(decrypt(encrypted)?))
+            }
+            Self::Xor => Box::new(XorReader::new(vec![11], reader)),
+            Self::Base64 => Box::new(Base64Reader::new(reader, &URL_SAFE)),
+            Self::Gzip => Box::new(GzipReader::new(reader).map_err(DeError::Io)?)
+        })
+    }
+}
+
+/// How the XML of a save is stored on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveEncoding {
+    /// Gzipped, base64 encoded and XORed with 11, also used on mobile
+    Windows,
+    /// AES-256-ECB encrypted with a static key and PKCS7 padding
+    Mac,
+    /// Plain XML, as exported by other tools
+    RawXml
+}
+
+impl SaveEncoding {
+    /// Length of the prefix `detect` needs
+    pub const DETECT_LEN: usize = DETECT_LEN;
+
+    /// Guesses the encoding from the first bytes of a save, the same way
+    /// `decode` does. Encrypted saves have no recognizable prefix, so
+    /// anything unknown is assumed to be one
+    pub fn detect(prefix: &[u8]) -> Self {
+        Self::from_layers(outer_layer(prefix).as_slice())
+    }
+
+    /// Encoding to write back a save that was read with these layers, saves
+    /// missing some of the layers are written back the way the game does
+    pub fn from_layers(layers: &[Layer]) -> Self {
+        match layers.first() {
+            Some(Layer::Aes) => Self::Mac,
+            Some(_) => Self::Windows,
+            None => Self::RawXml
+        }
+    }
+
+    /// Layers from the outermost one
+    pub fn layers(self) -> &'static [Layer] {
+        match self {
+            Self::Windows => &[Layer::Xor, Layer::Base64, Layer::Gzip],
+            Self::Mac => &[Layer::Aes],
+            Self::RawXml => &[]
+        }
+    }
+
+    /// Reader of the XML stored in this encoding
+    pub fn decode<'a, R: Read + 'a>(self, reader: R) -> DeResult<Box<dyn Read + 'a>> {
+        let mut reader: Box<dyn Read + 'a> = Box::new(reader);
+        for layer in self.layers() {
+            reader = layer.decode(reader)?;
+        }
+        Ok(reader)
+    }
+
+    pub fn encode(self, xml: &[u8]) -> SerResult<Vec<u8>> {
+        match self {
+            Self::Windows => ser::encode(xml),
+            Self::Mac => Ok(encrypt(xml)),
+            Self::RawXml => Ok(xml.to_vec())
+        }
+    }
+}
+
+fn is_xml(prefix: &[u8]) -> bool {
+    prefix.trim_ascii_start().starts_with(b"<")
+}
+
+/// Outermost layer of a save, the only one that can be AES
+fn outer_layer(prefix: &[u8]) -> Option<Layer> {
+    Layer::detect(prefix).or_else(|| (!prefix.is_empty() && !is_xml(prefix)).then_some(Layer::Aes))
+}
+
+/// First bytes of the reader, and a reader that still starts with them
+fn peek<'a>(mut reader: Box<dyn Read + 'a>) -> DeResult<(Vec<u8>, Box<dyn Read + 'a>)> {
+    let mut prefix = vec![];
+    (&mut reader).take(DETECT_LEN as u64)
+        .read_to_end(&mut prefix)
+        .map_err(DeError::Io)?;
+    Ok((prefix.clone(), Box::new(Cursor::new(prefix).chain(reader))))
+}
+
+/// Peels off exactly the layers the data has, so a fully encoded save, one
+/// that's only missing the XOR or base64 layer, a gzip file and plain XML
+/// can all be read. Data that isn't recognized is taken for a macOS save
+///
+/// Returns the peeled layers from the outermost one and a reader of the XML
+pub fn decode<'a, R: Read + 'a>(reader: R) -> DeResult<(Vec<Layer>, Box<dyn Read + 'a>)> {
+    let mut layers = vec![];
+    let mut reader: Box<dyn Read + 'a> = Box::new(reader);
+    loop {
+        let (prefix, peeked) = peek(reader)?;
+        let layer = if layers.is_empty() { outer_layer(&prefix) } else { Layer::detect(&prefix) };
+        let Some(layer) = layer else { return Ok((layers, peeked)) };
+        reader = layer.decode(peeked)?;
+        layers.push(layer);
+    }
+}
+
+fn cipher() -> Aes256 {
+    Aes256::new(GenericArray::from_slice(MAC_KEY))
+}
+
+fn decrypt(mut data: Vec<u8>) -> DeResult<Vec<u8>> {
+    if data.is_empty() || !data.len().is_multiple_of(BLOCK_LEN) {
+        return Err(DeError::Decryption)
+    }
+    let cipher = cipher();
+    for block in data.chunks_exact_mut(BLOCK_LEN) {
+        cipher.decrypt_block(GenericArray::from_mut_slice(block));
+    }
+    let padding = data[data.len() - 1] as usize;
+    let is_padding_valid = (1..=BLOCK_LEN).contains(&padding)
+        && data[data.len() - padding..].iter().all(|byte| *byte as usize == padding);
+    if !is_padding_valid {
+        return Err(DeError::Decryption)
+    }
+    data.truncate(data.len() - padding);
+    Ok(data)
+}
+
+fn encrypt(data: &[u8]) -> Vec<u8> {
+    let padding = BLOCK_LEN - data.len() % BLOCK_LEN;
+    let mut encrypted = data.to_vec();
+    encrypted.resize(data.len() + padding, padding as u8);
+    let cipher = cipher();
+    for block in encrypted.chunks_exact_mut(BLOCK_LEN) {
+        cipher.encrypt_block(GenericArray::from_mut_slice(block));
+    }
+    encrypted
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,93 @@
+use super::*;
+use crate::serde::{from_reader, from_any_reader, Value};
+
+const XML: &[u8] = br#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict><k>playerName</k><s>Tester</s></dict></plist>"#;
+
+fn read(mut reader: impl Read) -> Vec<u8> {
+    let mut read = vec![];
+    reader.read_to_end(&mut read).unwrap();
+    read
+}
+
+#[test]
+fn detects_encodings() {
+    for encoding in [SaveEncoding::Windows, SaveEncoding::Mac, SaveEncoding::RawXml] {
+        let encoded = encoding.encode(XML).unwrap();
+        assert_eq!(SaveEncoding::detect(&encoded[..SaveEncoding::DETECT_LEN]), encoding);
+
+        let (layers, reader) = decode(&encoded[..]).unwrap();
+        assert_eq!(layers, encoding.layers());
+        assert_eq!(read(reader), XML);
+    }
+}
+
+#[test]
+fn peels_only_present_layers() {
+    let encoded = SaveEncoding::Windows.encode(XML).unwrap();
+    let base64: Vec<u8> = encoded.iter().map(|byte| byte ^ 11).collect();
+    let gzipped = read(Layer::Base64.decode(&base64[..]).unwrap());
+
+    let (layers, reader) = decode(&base64[..]).unwrap();
+    assert_eq!(layers, [Layer::Base64, Layer::Gzip]);
+    assert_eq!(read(reader), XML);
+
+    let (layers, reader) = decode(&gzipped[..]).unwrap();
+    assert_eq!(layers, [Layer::Gzip]);
+    assert_eq!(read(reader), XML);
+}
+
+#[test]
+fn detects_what_decode_reports() {
+    let encoded = SaveEncoding::Windows.encode(XML).unwrap();
+    let base64: Vec<u8> = encoded.iter().map(|byte| byte ^ 11).collect();
+    let gzipped = read(Layer::Base64.decode(&base64[..]).unwrap());
+    let encrypted = SaveEncoding::Mac.encode(XML).unwrap();
+    for data in [&encoded[..], &base64[..], &gzipped[..], &encrypted[..], XML, b""] {
+        let (layers, _) = decode(data).unwrap();
+        let prefix = &data[..data.len().min(SaveEncoding::DETECT_LEN)];
+        assert_eq!(SaveEncoding::detect(prefix), SaveEncoding::from_layers(&layers));
+    }
+    assert_eq!(SaveEncoding::detect(b"H4sI"), SaveEncoding::Windows);
+    assert_eq!(SaveEncoding::detect(&[0x1f, 0x8b, 8, 0]), SaveEncoding::Windows);
+}
+
+#[test]
+fn deserializes_any_layers() {
+    let encoded = SaveEncoding::Windows.encode(XML).unwrap();
+    let base64: Vec<u8> = encoded.iter().map(|byte| byte ^ 11).collect();
+    let samples = [
+        (&encoded[..], SaveEncoding::Windows),
+        (&base64[..], SaveEncoding::Windows),
+        (XML, SaveEncoding::RawXml)
+    ];
+    for (data, encoding) in samples {
+        let data = from_any_reader::<Value, _>(data).unwrap();
+        assert_eq!(data.t.get("playerName"), Some(&Value::String("Tester".into())));
+        assert_eq!(data.encoding, encoding);
+    }
+}
+
+#[test]
+fn encrypts_mac_saves_with_padding() {
+    let encrypted = SaveEncoding::Mac.encode(&XML[..32]).unwrap();
+    // a whole block of padding is added to data that fills its last block
+    assert_eq!(encrypted.len(), 48);
+    assert_eq!(read(SaveEncoding::Mac.decode(&encrypted[..]).unwrap()), &XML[..32]);
+}
+
+#[test]
+fn rejects_damaged_mac_saves() {
+    let encrypted = SaveEncoding::Mac.encode(XML).unwrap();
+    assert!(matches!(
+        SaveEncoding::Mac.decode(&encrypted[..encrypted.len() - 1]).err(),
+        Some(DeError::Decryption)
+    ));
+}
+
+#[test]
+fn deserializes_mac_saves() {
+    let encrypted = SaveEncoding::Mac.encode(XML).unwrap();
+    let data = from_reader::<Value, _>(&encrypted[..]).unwrap();
+    assert_eq!(data.t.get("playerName"), Some(&Value::String("Tester".into())));
+    assert_eq!(data.encoding, SaveEncoding::Mac);
+}
@@ -28,6 +28,13 @@ pub enum DeError {
     ExpectedPlistVersion,
     ExpectedGjVersion,
     ExpectedEof,
+    /// Encrypted save with a wrong length or padding
+    Decryption,
+    /// Hash of a server response doesn't match its content
+    HashMismatch {
+        expected: String,
+        found: String
+    },
     Deserialization
 }
 
@@ -39,7 +46,25 @@ impl de::Error for DeError {
 
 impl Display for DeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        todo!()
+        match self {
+            Self::Custom(msg) => f.write_str(msg),
+            Self::Io(err) => write!(f, "io error: {err}"),
+            Self::XmlParse(err) => write!(f, "xml error: {err}"),
+            Self::XmlAttrParse(err) => write!(f, "xml attribute error: {err}"),
+            Self::NoXmlVersionInfo => f.write_str("xml declaration has no version"),
+            Self::UnexpectedOtherXml => f.write_str("unexpected xml content"),
+            Self::UnexpectedXmlTag => f.write_str("unexpected xml tag"),
+            Self::UnexpectedXmlText => f.write_str("unexpected xml text"),
+            Self::UnexpectedXmlAttr => f.write_str("unexpected xml attribute"),
+            Self::UnknownXmlTag => f.write_str("unknown xml tag"),
+            Self::ExpectedXmlVersion => f.write_str("expected xml declaration"),
+            Self::ExpectedPlistVersion => f.write_str("expected plist version"),
+            Self::ExpectedGjVersion => f.write_str("expected gjver attribute"),
+            Self::ExpectedEof => f.write_str("expected end of file"),
+            Self::Decryption => f.write_str("couldn't decrypt the save"),
+            Self::HashMismatch { expected, found } => write!(f, "response hash is {found}, expected {expected}"),
+            Self::Deserialization => f.write_str("value doesn't match the expected type")
+        }
     }
 }
 
@@ -50,7 +75,9 @@ pub type SerResult<T> = std::result::Result<T, SerError>;
 #[derive(Debug)]
 pub enum SerError {
     Custom(String),
-    XmlParse(XmlError)
+    Io(IoError),
+    XmlParse(XmlError),
+    Unsupported(&'static str)
 }
 
 impl ser::Error for SerError {
@@ -61,7 +88,12 @@ impl ser::Error for SerError {
 
 impl Display for SerError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        todo!()
+        match self {
+            Self::Custom(msg) => f.write_str(msg),
+            Self::Io(err) => write!(f, "io error: {err}"),
+            Self::XmlParse(err) => write!(f, "xml error: {err}"),
+            Self::Unsupported(kind) => write!(f, "{kind} can't be stored in a save")
+        }
     }
 }
 
@@ -0,0 +1,253 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Read,
+    path::Path
+};
+
+use base64::{
+    Engine,
+    alphabet,
+    engine::{GeneralPurpose, GeneralPurposeConfig, DecodePaddingMode}
+};
+use libflate::gzip::Decoder as GzipReader;
+use quick_xml::{
+    Reader as XmlReader,
+    events::Event as XmlEvent
+};
+
+use super::{
+    Header,
+    value::Value,
+    error::{DeError, DeResult}
+};
+
+/// Accepts a base64 tail that lost its padding
+const LENIENT_URL_SAFE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+        .with_decode_allow_trailing_bits(true)
+);
+
+/// What had to be thrown away to get the salvaged data
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecoveryReport {
+    /// Base64 characters after the damage point that couldn't be decoded
+    pub dropped_base64: usize,
+    /// Why the gzip stream ended early
+    pub gzip_error: Option<String>,
+    /// Length of the XML that could be decompressed
+    pub decoded_len: usize,
+    /// Why the XML ended early
+    pub xml_error: Option<String>,
+    /// Dicts that were never closed and had to be closed by the decoder
+    pub closed_dicts: usize,
+    /// Key whose value was cut off
+    pub incomplete_key: Option<String>
+}
+
+impl RecoveryReport {
+    /// The save decoded without any damage
+    pub fn is_intact(&self) -> bool {
+        *self == Self { decoded_len: self.decoded_len, ..Self::default() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Recovered {
+    pub header: Header,
+    pub value: Value,
+    pub report: RecoveryReport
+}
+
+/// Decodes as much of a damaged save as possible instead of failing on the
+/// first error like `from_reader` does
+pub fn recover<R: Read>(mut reader: R) -> DeResult<Recovered> {
+    let mut encoded = vec![];
+    if let Err(err) = reader.read_to_end(&mut encoded) {
+        return Err(DeError::Io(err))
+    }
+    let mut report = RecoveryReport::default();
+
+    let base64 = decode_xor(&encoded);
+    let gzipped = decode_base64(&base64, &mut report);
+    let xml = decode_gzip(&gzipped, &mut report);
+    report.decoded_len = xml.len();
+    let (header, value) = parse_xml(&xml, &mut report);
+
+    Ok(Recovered { header, value, report })
+}
+
+pub fn recover_file<P: AsRef<Path>>(path: P) -> DeResult<Recovered> {
+    match fs::File::open(path) {
+        Ok(file) => recover(file),
+        Err(err) => Err(DeError::Io(err))
+    }
+}
+
+fn decode_xor(encoded: &[u8]) -> Vec<u8> {
+    encoded.iter().map(|byte| byte ^ 11).collect()
+}
+
+fn decode_base64(base64: &[u8], report: &mut RecoveryReport) -> Vec<u8> {
+    let is_base64 = |byte: &u8| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_');
+    let mut valid_len = base64.iter().position(|byte| !is_base64(byte)).unwrap_or(base64.len());
+    // a single character of a quantum doesn't hold a whole byte
+    if valid_len % 4 == 1 { valid_len -= 1 }
+    let padding = base64[valid_len..].iter().take_while(|byte| **byte == b'=').count();
+    report.dropped_base64 = base64.len() - valid_len - padding;
+
+    match LENIENT_URL_SAFE.decode(&base64[..valid_len]) {
+        Ok(gzipped) => gzipped,
+        Err(_) => {
+            report.dropped_base64 = base64.len() - padding;
+            vec![]
+        }
+    }
+}
+
+fn decode_gzip(gzipped: &[u8], report: &mut RecoveryReport) -> Vec<u8> {
+    let mut xml = vec![];
+    let mut decoder = match GzipReader::new(gzipped) {
+        Ok(decoder) => decoder,
+        Err(err) => {
+            report.gzip_error = Some(err.to_string());
+            return xml
+        }
+    };
+    let mut buffer = [0; 4096];
+    loop {
+        match decoder.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => xml.extend_from_slice(&buffer[..len]),
+            Err(err) => {
+                // the block that was cut off is decoded up to the damage
+                xml.extend_from_slice(decoder.unread_decoded_data());
+                report.gzip_error = Some(err.to_string());
+                break
+            }
+        }
+    }
+    xml
+}
+
+#[derive(Default)]
+struct Dict {
+    entries: BTreeMap<String, Value>,
+    key: Option<String>
+}
+
+impl Dict {
+    fn into_value(self) -> Value {
+        let mut entries = self.entries;
+        if entries.remove("_isArr") != Some(Value::Bool(true)) {
+            return Value::Dict(entries)
+        }
+        let mut array = vec![];
+        while let Some(value) = entries.remove(&format!("k_{}", array.len())) {
+            array.push(value);
+        }
+        Value::Array(array)
+    }
+}
+
+/// Stores the value under the pending key of the innermost dict
+fn insert(stack: &mut [Dict], root: &mut Option<Value>, value: Value) {
+    match stack.last_mut() {
+        Some(dict) => if let Some(key) = dict.key.take() {
+            dict.entries.insert(key, value);
+        }
+        None => { root.get_or_insert(value); }
+    }
+}
+
+fn parse_xml(xml: &[u8], report: &mut RecoveryReport) -> (Header, Value) {
+    let mut reader = XmlReader::from_reader(xml);
+    let mut buffer = vec![];
+    let mut header = Header::default();
+    let mut stack: Vec<Dict> = vec![];
+    let mut root = None;
+    // tag and text of the value being read, only stored once the tag is closed
+    let mut open_value: Option<(Vec<u8>, String)> = None;
+
+    loop {
+        buffer.clear();
+        let event = match reader.read_event_into(&mut buffer) {
+            Ok(event) => event,
+            Err(err) => {
+                report.xml_error = Some(err.to_string());
+                break
+            }
+        };
+        match event {
+            XmlEvent::Decl(decl) => {
+                if let Ok(version) = decl.version() {
+                    header.xml_version = String::from_utf8_lossy(&version).into_owned();
+                }
+            }
+            XmlEvent::Start(tag) => match tag.name().into_inner() {
+                b"plist" => {
+                    for attr in tag.attributes().flatten() {
+                        let value = String::from_utf8_lossy(&attr.value).into_owned();
+                        match attr.key.into_inner() {
+                            b"version" => header.plist_version = value,
+                            b"gjver" => header.gj_version = value,
+                            _ => {}
+                        }
+                    }
+                }
+                b"d" | b"dict" => stack.push(Dict::default()),
+                name => open_value = Some((name.to_vec(), String::new()))
+            }
+            XmlEvent::Empty(tag) => match tag.name().into_inner() {
+                b"d" | b"dict" => insert(&mut stack, &mut root, Value::Dict(BTreeMap::new())),
+                b"t" => insert(&mut stack, &mut root, Value::Bool(true)),
+                _ => {}
+            }
+            XmlEvent::Text(text) => {
+                if let Some((_, content)) = &mut open_value {
+                    match text.unescape() {
+                        Ok(text) => content.push_str(&text),
+                        Err(_) => content.push_str(&String::from_utf8_lossy(&text))
+                    }
+                }
+            }
+            XmlEvent::End(tag) => match tag.name().into_inner() {
+                b"d" | b"dict" => {
+                    if let Some(dict) = stack.pop() {
+                        insert(&mut stack, &mut root, dict.into_value());
+                    }
+                }
+                _ => if let Some((name, content)) = open_value.take() {
+                    let value = match &name[..] {
+                        b"k" => {
+                            if let Some(dict) = stack.last_mut() { dict.key = Some(content) }
+                            continue
+                        }
+                        b"i" => content.parse().map(Value::Integer).unwrap_or(Value::String(content)),
+                        b"r" => content.parse().map(Value::Real).unwrap_or(Value::String(content)),
+                        b"t" => Value::Bool(true),
+                        _ => Value::String(content)
+                    };
+                    insert(&mut stack, &mut root, value);
+                }
+            }
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+    }
+
+    if let Some(dict) = stack.last() {
+        report.incomplete_key = dict.key.clone();
+    }
+    report.closed_dicts = stack.len();
+    while let Some(dict) = stack.pop() {
+        insert(&mut stack, &mut root, dict.into_value());
+    }
+
+    (header, root.unwrap_or_else(|| Value::Dict(BTreeMap::new())))
+}
+
+#[cfg(test)]
+mod tests;
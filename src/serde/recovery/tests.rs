@@ -0,0 +1,56 @@
+use super::*;
+use crate::{local_levels::LocalLevelsDB, serde::ser};
+
+fn local_levels_xml() -> String {
+    let mut xml = String::from(r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict><k>LLM_01</k><d><k>_isArr</k><t />"#);
+    for index in 0..40 {
+        xml += &format!(
+            "<k>k_{index}</k><d><k>k1</k><i>{}</i><k>k2</k><s>Level {index}</s><k>k5</k><s>Tester</s><k>k4</k><s>{}</s></d>",
+            1000 + index,
+            "H4sIAAAAAAAAC6WQwQ3DIAxFF3IlbGNI1FNm6ADeICv08KE".repeat(10)
+        );
+    }
+    xml += "</d><k>LLM_02</k><i>35</i></dict></plist>";
+    xml
+}
+
+#[test]
+fn recovers_intact_save() {
+    let xml = local_levels_xml();
+    let recovered = recover(&ser::encode(xml.as_bytes()).unwrap()[..]).unwrap();
+    assert!(recovered.report.is_intact(), "{:?}", recovered.report);
+    assert_eq!(recovered.report.decoded_len, xml.len());
+    assert_eq!(recovered.header, Header::default());
+    assert_eq!(LocalLevelsDB::salvage_levels(&recovered.value).len(), 40);
+    assert_eq!(recovered.value.get("LLM_02"), Some(&Value::Integer(35)));
+}
+
+#[test]
+fn recovers_levels_before_truncation() {
+    let encoded = ser::encode(local_levels_xml().as_bytes()).unwrap();
+    let truncated = &encoded[..encoded.len() / 2 + 1];
+    let recovered = recover(truncated).unwrap();
+
+    let report = &recovered.report;
+    assert!(!report.is_intact());
+    assert!(report.gzip_error.is_some());
+    assert_eq!(report.closed_dicts, 3);
+
+    let levels = LocalLevelsDB::salvage_levels(&recovered.value);
+    assert!(!levels.is_empty() && levels.len() < 40);
+    for (index, level) in levels.iter().enumerate() {
+        assert_eq!(level.name(), format!("Level {index}"));
+        assert_eq!(level.id(), Some(1000 + index as i32));
+    }
+}
+
+#[test]
+fn tolerates_damaged_base64_tail() {
+    let mut encoded = ser::encode(local_levels_xml().as_bytes()).unwrap();
+    let len = encoded.len();
+    encoded.truncate(len - 6);
+    encoded.extend_from_slice(&[0, 0, b'\n' ^ 11]);
+    let recovered = recover(&encoded[..]).unwrap();
+    assert_eq!(recovered.report.dropped_base64, 3);
+    assert!(!LocalLevelsDB::salvage_levels(&recovered.value).is_empty());
+}
@@ -0,0 +1,641 @@
+use std::fmt;
+use serde::{
+    de::{self, IntoDeserializer, value::BorrowedStrDeserializer},
+    ser::{self, Impossible, Serialize},
+    forward_to_deserialize_any
+};
+use super::error::{DeError, DeResult, SerError, SerResult};
+
+/// Separators of a RobTop server response, which stores objects as
+/// `1:123:2:Name` with `|` between objects and `#` between sections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Separators {
+    /// Between keys and values
+    pub field: &'static str,
+    /// Between objects of a list
+    pub record: &'static str
+}
+
+impl Separators {
+    /// Levels, users and most other objects
+    pub const DEFAULT: Self = Self { field: ":", record: "|" };
+    /// Songs, whose keys are `~|~` separated and objects `~:~` separated
+    pub const SONG: Self = Self { field: "~|~", record: "~:~" };
+    /// Comments, which are `~` separated with `|` between comments
+    pub const COMMENT: Self = Self { field: "~", record: "|" };
+}
+
+impl Default for Separators {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+const SECTION_SEPARATOR: char = '#';
+
+/// Sections of a response, which can each use different separators
+pub fn sections(response: &str) -> impl Iterator<Item = &str> {
+    response.split(SECTION_SEPARATOR)
+}
+
+pub fn from_str<'de, T: de::Deserialize<'de>>(input: &'de str) -> DeResult<T> {
+    from_str_with(input, Separators::DEFAULT)
+}
+
+/// Deserializes an object, or a list of objects when `T` is a sequence
+pub fn from_str_with<'de, T>(input: &'de str, separators: Separators) -> DeResult<T>
+where T: de::Deserialize<'de> {
+    T::deserialize(Deserializer::new(input, separators))
+}
+
+pub fn to_string<T: ?Sized + Serialize>(value: &T) -> SerResult<String> {
+    to_string_with(value, Separators::DEFAULT)
+}
+
+pub fn to_string_with<T>(value: &T, separators: Separators) -> SerResult<String>
+where T: ?Sized + Serialize {
+    value.serialize(Serializer { separators })
+}
+
+/// Whether a value can be taken for a number without changing it when
+/// written back, so `007` stays a string
+fn parse_canonical<T: std::str::FromStr + ToString>(text: &str) -> Option<T> {
+    text.parse().ok().filter(|parsed: &T| parsed.to_string() == text)
+}
+
+/// Deserializes a string that the RobTop format may have taken for a number.
+/// Only needed for structs with a flattened field, which makes serde buffer
+/// the values before knowing their type
+pub(crate) fn deserialize_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where D: de::Deserializer<'de> {
+    deserializer.deserialize_any(StringVisitor)
+}
+
+pub(crate) fn deserialize_optional_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where D: de::Deserializer<'de> {
+    deserializer.deserialize_any(StringVisitor).map(Some)
+}
+
+/// Serializes a list the way responses store level IDs, `1,2,3`
+pub(crate) fn serialize_comma_list<T, S>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where T: ToString, S: ser::Serializer {
+    let values: Vec<String> = values.iter().map(T::to_string).collect();
+    serializer.serialize_str(&values.join(","))
+}
+
+pub(crate) fn deserialize_comma_list<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where T: std::str::FromStr, D: de::Deserializer<'de> {
+    let values = deserializer.deserialize_any(StringVisitor)?;
+    values.split(',')
+        .filter(|value| !value.is_empty())
+        .map(|value| value.parse().map_err(|_| de::Error::custom(format!("invalid list item {value:?}"))))
+        .collect()
+}
+
+struct StringVisitor;
+
+impl de::Visitor<'_> for StringVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<String, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<String, E> {
+        Ok(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<String, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<String, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<String, E> {
+        Ok(v.to_string())
+    }
+}
+
+/// Deserializer of a whole object or list of objects
+pub struct Deserializer<'de> {
+    input: &'de str,
+    separators: Separators
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn new(input: &'de str, separators: Separators) -> Self {
+        Self { input, separators }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = DeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        let fields = if self.input.is_empty() { None } else { Some(self.input.split(self.separators.field)) };
+        visitor.visit_map(FieldReader { fields: fields.into_iter().flatten(), value: None })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V
+    ) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        let separators = self.separators;
+        visitor.visit_seq(RecordReader {
+            records: self.input.split(separators.record).filter(|record| !record.is_empty()),
+            separators
+        })
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        if self.input.is_empty() { visitor.visit_none() } else { visitor.visit_some(self) }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct enum identifier
+    }
+}
+
+struct RecordReader<'de, I: Iterator<Item = &'de str>> {
+    records: I,
+    separators: Separators
+}
+
+impl<'de, I: Iterator<Item = &'de str>> de::SeqAccess<'de> for RecordReader<'de, I> {
+    type Error = DeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> DeResult<Option<T::Value>>
+    where T: de::DeserializeSeed<'de> {
+        match self.records.next() {
+            Some(record) => seed.deserialize(Deserializer::new(record, self.separators)).map(Some),
+            None => Ok(None)
+        }
+    }
+}
+
+struct FieldReader<'de, I: Iterator<Item = &'de str>> {
+    fields: I,
+    value: Option<&'de str>
+}
+
+impl<'de, I: Iterator<Item = &'de str>> de::MapAccess<'de> for FieldReader<'de, I> {
+    type Error = DeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> DeResult<Option<K::Value>>
+    where K: de::DeserializeSeed<'de> {
+        let Some(key) = self.fields.next() else { return Ok(None) };
+        match self.fields.next() {
+            Some(value) => self.value = Some(value),
+            None => return Err(DeError::Custom(format!("key {key} has no value")))
+        }
+        seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> DeResult<V::Value>
+    where V: de::DeserializeSeed<'de> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(ValueDeserializer(value)),
+            None => Err(DeError::Deserialization)
+        }
+    }
+}
+
+macro_rules! deserialize_number {
+    ($deserialize: ident => $visit: ident) => {
+        fn $deserialize<V>(self, visitor: V) -> DeResult<V::Value>
+        where V: de::Visitor<'de> {
+            match self.0.parse() {
+                Ok(parsed) => visitor.$visit(parsed),
+                Err(_) => Err(DeError::Deserialization)
+            }
+        }
+    };
+}
+
+/// Deserializer of a single value, which is always stored as text
+struct ValueDeserializer<'de>(&'de str);
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = DeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        if let Some(int) = parse_canonical(self.0) {
+            visitor.visit_i64(int)
+        } else if let Some(real) = parse_canonical::<f64>(self.0).filter(|real| real.is_finite()) {
+            visitor.visit_f64(real)
+        } else {
+            visitor.visit_borrowed_str(self.0)
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        match self.0 {
+            "1" | "true" => visitor.visit_bool(true),
+            "0" | "false" | "" => visitor.visit_bool(false),
+            _ => Err(DeError::Deserialization)
+        }
+    }
+
+    deserialize_number!(deserialize_i8 => visit_i8);
+    deserialize_number!(deserialize_i16 => visit_i16);
+    deserialize_number!(deserialize_i32 => visit_i32);
+    deserialize_number!(deserialize_i64 => visit_i64);
+    deserialize_number!(deserialize_u8 => visit_u8);
+    deserialize_number!(deserialize_u16 => visit_u16);
+    deserialize_number!(deserialize_u32 => visit_u32);
+    deserialize_number!(deserialize_u64 => visit_u64);
+    deserialize_number!(deserialize_f32 => visit_f32);
+    deserialize_number!(deserialize_f64 => visit_f64);
+
+    fn deserialize_str<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        if self.0.is_empty() { visitor.visit_none() } else { visitor.visit_some(self) }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char bytes byte_buf unit unit_struct seq tuple tuple_struct
+        map struct identifier
+    }
+}
+
+/// Serializer of a whole object or list of objects
+pub struct Serializer {
+    separators: Separators
+}
+
+macro_rules! serialize_value {
+    ($($serialize: ident => $value_type: ty),*) => {$(
+        fn $serialize(self, v: $value_type) -> SerResult<Self::Ok> {
+            ValueSerializer.$serialize(v).map(Option::unwrap_or_default)
+        }
+    )*};
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = String;
+    type Error = SerError;
+    type SerializeSeq = RecordWriter;
+    type SerializeTuple = Impossible<String, SerError>;
+    type SerializeTupleStruct = Impossible<String, SerError>;
+    type SerializeTupleVariant = Impossible<String, SerError>;
+    type SerializeMap = FieldWriter;
+    type SerializeStruct = FieldWriter;
+    type SerializeStructVariant = Impossible<String, SerError>;
+
+    serialize_value!(
+        serialize_bool => bool,
+        serialize_i8 => i8, serialize_i16 => i16, serialize_i32 => i32, serialize_i64 => i64,
+        serialize_u8 => u8, serialize_u16 => u16, serialize_u32 => u32, serialize_u64 => u64,
+        serialize_f32 => f32, serialize_f64 => f64,
+        serialize_char => char, serialize_str => &str, serialize_bytes => &[u8]
+    );
+
+    fn serialize_none(self) -> SerResult<String> {
+        Ok(String::new())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> SerResult<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> SerResult<String> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> SerResult<String> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str
+    ) -> SerResult<String> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T
+    ) -> SerResult<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T
+    ) -> SerResult<String> {
+        Err(SerError::Unsupported("enum variants with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> SerResult<RecordWriter> {
+        Ok(RecordWriter { separators: self.separators, records: vec![] })
+    }
+
+    fn serialize_tuple(self, _len: usize) -> SerResult<Self::SerializeTuple> {
+        Err(SerError::Unsupported("tuples"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize
+    ) -> SerResult<Self::SerializeTupleStruct> {
+        Err(SerError::Unsupported("tuple structs"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize
+    ) -> SerResult<Self::SerializeTupleVariant> {
+        Err(SerError::Unsupported("enum variants with data"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> SerResult<FieldWriter> {
+        Ok(FieldWriter { separators: self.separators, fields: vec![], key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> SerResult<FieldWriter> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize
+    ) -> SerResult<Self::SerializeStructVariant> {
+        Err(SerError::Unsupported("enum variants with data"))
+    }
+}
+
+pub struct RecordWriter {
+    separators: Separators,
+    records: Vec<String>
+}
+
+impl ser::SerializeSeq for RecordWriter {
+    type Ok = String;
+    type Error = SerError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> SerResult<()> {
+        self.records.push(to_string_with(value, self.separators)?);
+        Ok(())
+    }
+
+    fn end(self) -> SerResult<String> {
+        Ok(self.records.join(self.separators.record))
+    }
+}
+
+pub struct FieldWriter {
+    separators: Separators,
+    fields: Vec<String>,
+    key: Option<String>
+}
+
+impl FieldWriter {
+    fn write_field<T: ?Sized + Serialize>(&mut self, key: String, value: &T) -> SerResult<()> {
+        // omitted like `None` in the plist format
+        if let Some(value) = value.serialize(ValueSerializer)? {
+            self.fields.push(key);
+            self.fields.push(value);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for FieldWriter {
+    type Ok = String;
+    type Error = SerError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> SerResult<()> {
+        self.key = key.serialize(ValueSerializer)?;
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> SerResult<()> {
+        match self.key.take() {
+            Some(key) => self.write_field(key, value),
+            None => Err(SerError::Unsupported("empty keys"))
+        }
+    }
+
+    fn end(self) -> SerResult<String> {
+        Ok(self.fields.join(self.separators.field))
+    }
+}
+
+impl ser::SerializeStruct for FieldWriter {
+    type Ok = String;
+    type Error = SerError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> SerResult<()> {
+        self.write_field(key.to_string(), value)
+    }
+
+    fn end(self) -> SerResult<String> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Serializer of a single value, `None` if the field should be left out
+struct ValueSerializer;
+
+macro_rules! serialize_to_string {
+    ($($serialize: ident => $value_type: ty),*) => {$(
+        fn $serialize(self, v: $value_type) -> SerResult<Option<String>> {
+            Ok(Some(v.to_string()))
+        }
+    )*};
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Option<String>;
+    type Error = SerError;
+    type SerializeSeq = Impossible<Option<String>, SerError>;
+    type SerializeTuple = Impossible<Option<String>, SerError>;
+    type SerializeTupleStruct = Impossible<Option<String>, SerError>;
+    type SerializeTupleVariant = Impossible<Option<String>, SerError>;
+    type SerializeMap = Impossible<Option<String>, SerError>;
+    type SerializeStruct = Impossible<Option<String>, SerError>;
+    type SerializeStructVariant = Impossible<Option<String>, SerError>;
+
+    serialize_to_string!(
+        serialize_i8 => i8, serialize_i16 => i16, serialize_i32 => i32, serialize_i64 => i64,
+        serialize_u8 => u8, serialize_u16 => u16, serialize_u32 => u32, serialize_u64 => u64,
+        serialize_f32 => f32, serialize_f64 => f64,
+        serialize_char => char, serialize_str => &str
+    );
+
+    fn serialize_bool(self, v: bool) -> SerResult<Option<String>> {
+        Ok(Some(String::from(if v { "1" } else { "0" })))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> SerResult<Option<String>> {
+        Err(SerError::Unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> SerResult<Option<String>> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> SerResult<Option<String>> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> SerResult<Option<String>> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> SerResult<Option<String>> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str
+    ) -> SerResult<Option<String>> {
+        Ok(Some(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T
+    ) -> SerResult<Option<String>> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T
+    ) -> SerResult<Option<String>> {
+        Err(SerError::Unsupported("enum variants with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> SerResult<Self::SerializeSeq> {
+        Err(SerError::Unsupported("nested lists"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> SerResult<Self::SerializeTuple> {
+        Err(SerError::Unsupported("tuples"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize
+    ) -> SerResult<Self::SerializeTupleStruct> {
+        Err(SerError::Unsupported("tuple structs"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize
+    ) -> SerResult<Self::SerializeTupleVariant> {
+        Err(SerError::Unsupported("enum variants with data"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> SerResult<Self::SerializeMap> {
+        Err(SerError::Unsupported("nested objects"))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> SerResult<Self::SerializeStruct> {
+        Err(SerError::Unsupported("nested objects"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize
+    ) -> SerResult<Self::SerializeStructVariant> {
+        Err(SerError::Unsupported("enum variants with data"))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use super::*;
+use crate::{local_levels::Level, serde::Value};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Song {
+    #[serde(rename = "1")]
+    id: i32,
+    #[serde(rename = "2")]
+    name: String,
+    #[serde(rename = "5")]
+    size_mb: f32,
+    #[serde(rename = "9")]
+    #[serde(default)]
+    is_verified: bool,
+    #[serde(rename = "10")]
+    #[serde(default)]
+    url: Option<String>
+}
+
+#[test]
+fn deserializes_objects() {
+    let song: Song = from_str_with("1~|~803223~|~2~|~Xtrullor - Supernova~|~5~|~9.56~|~9~|~1", Separators::SONG).unwrap();
+    assert_eq!(song, Song { id: 803223, name: String::from("Xtrullor - Supernova"), size_mb: 9.56, is_verified: true, url: None });
+}
+
+#[test]
+fn deserializes_lists() {
+    let songs: Vec<Song> = from_str_with(
+        "1~|~1~|~2~|~First~|~5~|~1.5~:~1~|~2~|~2~|~Second~|~5~|~2~|~10~|~https://example.com~:~",
+        Separators::SONG
+    ).unwrap();
+    assert_eq!(songs.len(), 2);
+    assert_eq!(songs[1].url.as_deref(), Some("https://example.com"));
+    assert!(from_str::<Vec<Song>>("").unwrap().is_empty());
+}
+
+#[test]
+fn rejects_key_without_value() {
+    assert!(from_str::<BTreeMap<String, String>>("1:2:3").is_err());
+}
+
+#[test]
+fn deserializes_levels_from_responses() {
+    let levels: Vec<Level> = from_str("1:128:2:1234:3:QSBsZXZlbA==:4:H4sIAAAA:6:3935672:30:0:35:007|1:129:2:Second").unwrap();
+    assert_eq!(levels[0].id(), Some(128));
+    // numeric names aren't taken for numbers
+    assert_eq!(levels[0].name(), "1234");
+    assert_eq!(levels[0].level_string(), Some("H4sIAAAA"));
+    assert_eq!(levels[0].custom_song_id(), Some(7));
+    assert_eq!(levels[0].original_id(), None);
+    assert_eq!(levels[1].name(), "Second");
+    assert_eq!(levels[1].level_string(), None);
+
+    let level = serde_json::to_value(&levels[0]).unwrap();
+    assert_eq!(level["3"], "QSBsZXZlbA==");
+    assert_eq!(levels[0].player_id(), Some(3935672));
+}
+
+#[test]
+fn serializes_round_trip() {
+    let songs = vec![
+        Song { id: 1, name: String::from("First"), size_mb: 1.5, is_verified: false, url: None },
+        Song { id: 2, name: String::from("007"), size_mb: 2.0, is_verified: true, url: Some(String::from("https://example.com")) }
+    ];
+    let text = to_string_with(&songs, Separators::SONG).unwrap();
+    assert_eq!(text, "1~|~1~|~2~|~First~|~5~|~1.5~|~9~|~0~:~1~|~2~|~2~|~007~|~5~|~2~|~9~|~1~|~10~|~https://example.com");
+    assert_eq!(from_str_with::<Vec<Song>>(&text, Separators::SONG).unwrap(), songs);
+
+    let value: BTreeMap<String, Value> = from_str("1:5:2:007").unwrap();
+    assert_eq!(value["1"], Value::Integer(5));
+    assert_eq!(value["2"], Value::String(String::from("007")));
+    assert!(matches!(to_string(&vec![value.clone()]), Ok(text) if text == "1:5:2:007"));
+}
+
+#[test]
+fn splits_sections() {
+    let response = "1:128|1:129#3935672:Tester:71#1:50:0";
+    assert_eq!(sections(response).collect::<Vec<_>>(), ["1:128|1:129", "3935672:Tester:71", "1:50:0"]);
+}
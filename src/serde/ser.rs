@@ -1,20 +1,21 @@
-use std::io::Cursor;
-use serde::{ser, serde_if_integer128};
+use std::{
+    path::Path,
+    fs::File,
+    io::{Cursor, Write}
+};
+use serde::ser::{self, Serialize};
+
+use base64::{
+    Engine,
+    engine::general_purpose::URL_SAFE
+};
+use libflate::gzip::Encoder as GzipWriter;
 use quick_xml::{
     Writer as XmlWriter,
     events::{self as xml_events, Event as XmlEvent}
 };
 use super::error::{ SerError, SerResult };
-
-pub struct Serializer {
-    pub writer: XmlWriter<Cursor<Vec<u8>>> // temporarily public
-}
-
-impl Serializer {
-    pub fn new() -> Self { // temporarily public
-        Self { writer: XmlWriter::new(Cursor::new(vec![])) }
-    }
-}
+use super::{DataWithHeader, SaveEncoding};
 
 macro_rules! write_event {
     ($writer: expr, $event: expr) => {
@@ -27,15 +28,161 @@ macro_rules! write_event {
 macro_rules! serialize_type {
     ($serialize: ident => $value_type: ident, $tag: expr) => {
         fn $serialize(self, v: $value_type) -> SerResult<()> {
-            write_event!(self.writer, XmlEvent::Start(xml_events::BytesStart::new($tag)));
-            write_event!(self.writer, XmlEvent::Text(xml_events::BytesText::new(&v.to_string())));
-            write_event!(self.writer, XmlEvent::End(xml_events::BytesEnd::new($tag)));
-            Ok(())
+            self.write_text($tag, &v.to_string())
         }
     };
 }
 
-impl<'a> ser::Serializer for &'a mut Serializer {
+pub struct Serializer {
+    pub writer: XmlWriter<Cursor<Vec<u8>>>, // temporarily public
+    depth: usize,
+    array_index: usize,
+    /// Key passed to `SerializeMap::serialize_key`, written with its value
+    pending_key: Option<String>
+}
+
+impl Default for Serializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer {
+    pub fn new() -> Self { // temporarily public
+        Self::with_depth(0)
+    }
+
+    fn with_depth(depth: usize) -> Self {
+        Self {
+            writer: XmlWriter::new(Cursor::new(vec![])),
+            depth,
+            array_index: 0,
+            pending_key: None
+        }
+    }
+
+    fn into_inner(self) -> Vec<u8> {
+        self.writer.into_inner().into_inner()
+    }
+
+    /// Serializes the value one level deeper, empty if the value is omitted
+    /// from the save (`None` and `false`)
+    fn serialize_nested<T: ?Sized + Serialize>(&self, value: &T) -> SerResult<Vec<u8>> {
+        let mut nested = Self::with_depth(self.depth + 1);
+        value.serialize(&mut nested)?;
+        Ok(nested.into_inner())
+    }
+
+    fn write_text(&mut self, tag: &str, text: &str) -> SerResult<()> {
+        write_event!(self.writer, XmlEvent::Start(xml_events::BytesStart::new(tag)));
+        write_event!(self.writer, XmlEvent::Text(xml_events::BytesText::new(text)));
+        write_event!(self.writer, XmlEvent::End(xml_events::BytesEnd::new(tag)));
+        Ok(())
+    }
+
+    fn write_entry<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> SerResult<bool> {
+        let value = self.serialize_nested(value)?;
+        if value.is_empty() { return Ok(false) }
+        self.write_text("k", key)?;
+        if let Err(err) = self.writer.inner().write_all(&value) {
+            return Err(SerError::Io(err))
+        }
+        Ok(true)
+    }
+
+    fn dict_tag(&self) -> &'static str {
+        if self.depth == 0 { "dict" } else { "d" }
+    }
+
+    fn start_dict(&mut self) -> SerResult<()> {
+        write_event!(self.writer, XmlEvent::Start(xml_events::BytesStart::new(self.dict_tag())));
+        Ok(())
+    }
+
+    fn end_dict(&mut self) -> SerResult<()> {
+        write_event!(self.writer, XmlEvent::End(xml_events::BytesEnd::new(self.dict_tag())));
+        Ok(())
+    }
+
+    fn start_array(&mut self) -> SerResult<()> {
+        self.start_dict()?;
+        self.write_text("k", "_isArr")?;
+        write_event!(self.writer, XmlEvent::Empty(xml_events::BytesStart::new("t")));
+        self.array_index = 0;
+        Ok(())
+    }
+
+    fn write_element<T: ?Sized + Serialize>(&mut self, value: &T) -> SerResult<()> {
+        // omitted elements keep their index so that the rest stay in place,
+        // only trailing ones are lost since nothing marks the end of the array
+        let key = format!("k_{}", self.array_index);
+        self.array_index += 1;
+        self.write_entry(&key, value)?;
+        Ok(())
+    }
+}
+
+/// Writes the data as plain plist XML
+pub fn to_xml<T: Serialize>(value: &DataWithHeader<T>) -> SerResult<Vec<u8>> {
+    let header = &value.header;
+    let mut serializer = Serializer::new();
+    write_event!(serializer.writer, XmlEvent::Decl(
+        xml_events::BytesDecl::new(&header.xml_version, None, None)
+    ));
+    let mut plist = xml_events::BytesStart::new("plist");
+    plist.push_attribute(("version", header.plist_version.as_str()));
+    plist.push_attribute(("gjver", header.gj_version.as_str()));
+    write_event!(serializer.writer, XmlEvent::Start(plist));
+    value.t.serialize(&mut serializer)?;
+    write_event!(serializer.writer, XmlEvent::End(xml_events::BytesEnd::new("plist")));
+    Ok(serializer.into_inner())
+}
+
+/// Applies the gzip and base64 layers, what's stored without the xor layer
+pub fn encode_base64(xml: &[u8]) -> SerResult<String> {
+    let gzipped = GzipWriter::new(vec![])
+        .and_then(|mut writer| {
+            writer.write_all(xml)?;
+            writer.finish().into_result()
+        });
+    match gzipped {
+        Ok(gzipped) => Ok(URL_SAFE.encode(gzipped)),
+        Err(err) => Err(SerError::Io(err))
+    }
+}
+
+/// Applies the gzip, base64 and xor layers the game stores its saves with
+pub fn encode(xml: &[u8]) -> SerResult<Vec<u8>> {
+    Ok(encode_base64(xml)?.into_bytes().into_iter()
+        .map(|byte| byte ^ 11)
+        .collect())
+}
+
+/// Serializes and encodes the data the same way the game does
+pub fn to_vec<T: Serialize>(value: &DataWithHeader<T>) -> SerResult<Vec<u8>> {
+    encode(&to_xml(value)?)
+}
+
+/// Like `to_vec`, for saves of other platforms
+pub fn to_vec_with_encoding<T: Serialize>(value: &DataWithHeader<T>, encoding: SaveEncoding) -> SerResult<Vec<u8>> {
+    encoding.encode(&to_xml(value)?)
+}
+
+pub fn to_writer<T: Serialize, W: Write>(mut writer: W, value: &DataWithHeader<T>) -> SerResult<()> {
+    match writer.write_all(&to_vec(value)?) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(SerError::Io(err))
+    }
+}
+
+pub fn to_file<T: Serialize, P: AsRef<Path>>(path: P, value: &DataWithHeader<T>) -> SerResult<()> {
+    match File::create(path) {
+        Ok(file) => to_writer(file, value),
+        Err(err) => Err(SerError::Io(err))
+    }
+}
+
+impl ser::Serializer for &mut Serializer {
     type Ok = ();
     type Error = SerError;
     type SerializeSeq = Self;
@@ -46,8 +193,11 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     type SerializeStruct = Self;
     type SerializeStructVariant = Self;
 
+    // the game omits false values instead of writing them
     fn serialize_bool(self, v: bool) -> SerResult<()> {
-        write_event!(self.writer, XmlEvent::Empty(xml_events::BytesStart::new("i")));
+        if v {
+            write_event!(self.writer, XmlEvent::Empty(xml_events::BytesStart::new("t")));
+        }
         Ok(())
     }
 
@@ -55,245 +205,331 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     serialize_type!(serialize_i16 => i16, "i");
     serialize_type!(serialize_i32 => i32, "i");
     serialize_type!(serialize_i64 => i64, "i");
+    serialize_type!(serialize_i128 => i128, "i");
 
     serialize_type!(serialize_u8 => u8, "i");
     serialize_type!(serialize_u16 => u16, "i");
     serialize_type!(serialize_u32 => u32, "i");
     serialize_type!(serialize_u64 => u64, "i");
-
-    serde_if_integer128! {
-        serialize_type!(serialize_u128 => u128, "i");
-        serialize_type!(serialize_i128 => i128, "i");
-    }
+    serialize_type!(serialize_u128 => u128, "i");
 
     serialize_type!(serialize_f32 => f32, "r");
     serialize_type!(serialize_f64 => f64, "r");
 
-    fn serialize_char(self, v: char) -> SerResult<()> {
-        todo!()
-    }
+    serialize_type!(serialize_char => char, "s");
 
     fn serialize_str(self, v: &str) -> SerResult<()> {
-        todo!()
+        self.write_text("s", v)
     }
 
-    fn serialize_bytes(self, v: &[u8]) -> SerResult<()> {
-        todo!()
+    fn serialize_bytes(self, _v: &[u8]) -> SerResult<()> {
+        Err(SerError::Unsupported("bytes"))
     }
 
     fn serialize_none(self) -> SerResult<()> {
-        todo!()
+        Ok(())
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> SerResult<()>
-    where
-        T: serde::Serialize {
-        todo!()
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> SerResult<()> {
+        value.serialize(self)
     }
 
     fn serialize_unit(self) -> SerResult<()> {
-        todo!()
+        Ok(())
     }
 
-    fn serialize_unit_struct(self, name: &'static str) -> SerResult<()> {
-        todo!()
+    fn serialize_unit_struct(self, _name: &'static str) -> SerResult<()> {
+        Ok(())
     }
 
     fn serialize_unit_variant(
         self,
-        name: &'static str,
-        variant_index: u32,
+        _name: &'static str,
+        _variant_index: u32,
         variant: &'static str,
     ) -> SerResult<()> {
-        todo!()
+        self.serialize_str(variant)
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
-        name: &'static str,
+        _name: &'static str,
         value: &T,
-    ) -> SerResult<()>
-    where
-        T: serde::Serialize {
-        todo!()
+    ) -> SerResult<()> {
+        value.serialize(self)
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
         self,
-        name: &'static str,
-        variant_index: u32,
-        variant: &'static str,
-        value: &T,
-    ) -> SerResult<()>
-    where
-        T: serde::Serialize {
-        todo!()
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> SerResult<()> {
+        Err(SerError::Unsupported("newtype variant"))
     }
 
-    fn serialize_seq(self, len: Option<usize>) -> SerResult<Self> {
-        todo!()
+    fn serialize_seq(self, _len: Option<usize>) -> SerResult<Self> {
+        self.start_array()?;
+        Ok(self)
     }
 
     fn serialize_tuple(self, len: usize) -> SerResult<Self> {
-        todo!()
+        self.serialize_seq(Some(len))
     }
 
     fn serialize_tuple_struct(
         self,
-        name: &'static str,
+        _name: &'static str,
         len: usize,
     ) -> SerResult<Self> {
-        todo!()
+        self.serialize_seq(Some(len))
     }
 
     fn serialize_tuple_variant(
         self,
-        name: &'static str,
-        variant_index: u32,
-        variant: &'static str,
-        len: usize,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
     ) -> SerResult<Self> {
-        todo!()
+        Err(SerError::Unsupported("tuple variant"))
     }
 
-    fn serialize_map(self, len: Option<usize>) -> SerResult<Self> {
-        todo!()
+    fn serialize_map(self, _len: Option<usize>) -> SerResult<Self> {
+        self.start_dict()?;
+        Ok(self)
     }
 
     fn serialize_struct(
         self,
-        name: &'static str,
+        _name: &'static str,
         len: usize,
     ) -> SerResult<Self> {
-        todo!()
+        self.serialize_map(Some(len))
     }
 
     fn serialize_struct_variant(
         self,
-        name: &'static str,
-        variant_index: u32,
-        variant: &'static str,
-        len: usize,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
     ) -> SerResult<Self> {
-        todo!()
+        Err(SerError::Unsupported("struct variant"))
     }
 }
 
-impl<'a> ser::SerializeSeq for &'a mut Serializer {
+impl ser::SerializeSeq for &mut Serializer {
     type Ok = ();
     type Error = SerError;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
-    where
-        T: serde::Serialize {
-        todo!()
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> SerResult<()> {
+        self.write_element(value)
     }
 
     fn end(self) -> SerResult<()> {
-        todo!()
+        self.end_dict()
     }
 }
 
-impl<'a> ser::SerializeTuple for &'a mut Serializer {
+impl ser::SerializeTuple for &mut Serializer {
     type Ok = ();
     type Error = SerError;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
-    where
-        T: serde::Serialize {
-        todo!()
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> SerResult<()> {
+        self.write_element(value)
     }
 
     fn end(self) -> SerResult<()> {
-        todo!()
+        self.end_dict()
     }
 }
 
-impl<'a> ser::SerializeTupleStruct for &'a mut Serializer {
+impl ser::SerializeTupleStruct for &mut Serializer {
     type Ok = ();
     type Error = SerError;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
-    where
-        T: serde::Serialize {
-        todo!()
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> SerResult<()> {
+        self.write_element(value)
     }
 
     fn end(self) -> SerResult<()> {
-        todo!()
+        self.end_dict()
     }
 }
 
-impl<'a> ser::SerializeTupleVariant for &'a mut Serializer {
+impl ser::SerializeTupleVariant for &mut Serializer {
     type Ok = ();
     type Error = SerError;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
-    where
-        T: serde::Serialize {
-        todo!()
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _value: &T) -> SerResult<()> {
+        unreachable!()
     }
 
     fn end(self) -> SerResult<()> {
-        todo!()
+        unreachable!()
     }
 }
 
-impl<'a> ser::SerializeMap for &'a mut Serializer {
+impl ser::SerializeMap for &mut Serializer {
     type Ok = ();
     type Error = SerError;
 
-    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
-    where
-        T: serde::Serialize {
-        todo!()
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> SerResult<()> {
+        self.pending_key = Some(key.serialize(KeySerializer)?);
+        Ok(())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
-    where
-        T: serde::Serialize {
-        todo!()
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> SerResult<()> {
+        let key = self.pending_key.take()
+            .ok_or_else(|| SerError::Custom(String::from("map value serialized before its key")))?;
+        self.write_entry(&key, value)?;
+        Ok(())
+    }
+
+    fn serialize_entry<K: ?Sized + Serialize, V: ?Sized + Serialize>(
+        &mut self,
+        key: &K,
+        value: &V
+    ) -> SerResult<()> {
+        let key = key.serialize(KeySerializer)?;
+        self.write_entry(&key, value)?;
+        Ok(())
     }
 
     fn end(self) -> SerResult<()> {
-        todo!()
+        self.end_dict()
     }
 }
 
-impl<'a> ser::SerializeStruct for &'a mut Serializer {
+impl ser::SerializeStruct for &mut Serializer {
     type Ok = ();
     type Error = SerError;
 
-    fn serialize_field<T: ?Sized>(
+    fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
         key: &'static str,
         value: &T,
-    ) -> Result<(), Self::Error>
-    where
-        T: serde::Serialize {
-        todo!()
+    ) -> SerResult<()> {
+        self.write_entry(key, value)?;
+        Ok(())
     }
 
     fn end(self) -> SerResult<()> {
-        todo!()
+        self.end_dict()
     }
 }
 
-impl<'a> ser::SerializeStructVariant for &'a mut Serializer {
+impl ser::SerializeStructVariant for &mut Serializer {
     type Ok = ();
     type Error = SerError;
 
-    fn serialize_field<T: ?Sized>(
+    fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
-    where
-        T: serde::Serialize {
-        todo!()
+        _key: &'static str,
+        _value: &T,
+    ) -> SerResult<()> {
+        unreachable!()
     }
 
     fn end(self) -> SerResult<()> {
-        todo!()
+        unreachable!()
+    }
+}
+
+/// Turns map keys into the text of `<k>` tags
+struct KeySerializer;
+
+macro_rules! serialize_key {
+    ($serialize: ident => $value_type: ident) => {
+        fn $serialize(self, v: $value_type) -> SerResult<String> {
+            Ok(v.to_string())
+        }
+    };
+}
+
+macro_rules! unsupported_key {
+    ($serialize: ident($($arg: ty),*) -> $ok: ty) => {
+        fn $serialize(self, $(_: $arg),*) -> SerResult<$ok> {
+            Err(SerError::Unsupported("non-string map key"))
+        }
+    };
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = SerError;
+    type SerializeSeq = ser::Impossible<String, SerError>;
+    type SerializeTuple = ser::Impossible<String, SerError>;
+    type SerializeTupleStruct = ser::Impossible<String, SerError>;
+    type SerializeTupleVariant = ser::Impossible<String, SerError>;
+    type SerializeMap = ser::Impossible<String, SerError>;
+    type SerializeStruct = ser::Impossible<String, SerError>;
+    type SerializeStructVariant = ser::Impossible<String, SerError>;
+
+    serialize_key!(serialize_bool => bool);
+    serialize_key!(serialize_i8 => i8);
+    serialize_key!(serialize_i16 => i16);
+    serialize_key!(serialize_i32 => i32);
+    serialize_key!(serialize_i64 => i64);
+    serialize_key!(serialize_i128 => i128);
+    serialize_key!(serialize_u8 => u8);
+    serialize_key!(serialize_u16 => u16);
+    serialize_key!(serialize_u32 => u32);
+    serialize_key!(serialize_u64 => u64);
+    serialize_key!(serialize_u128 => u128);
+    serialize_key!(serialize_f32 => f32);
+    serialize_key!(serialize_f64 => f64);
+    serialize_key!(serialize_char => char);
+
+    fn serialize_str(self, v: &str) -> SerResult<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> SerResult<String> {
+        Ok(variant.to_string())
     }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> SerResult<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> SerResult<String> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> SerResult<String> {
+        Err(SerError::Unsupported("non-string map key"))
+    }
+
+    unsupported_key!(serialize_bytes(&[u8]) -> String);
+    unsupported_key!(serialize_none() -> String);
+    unsupported_key!(serialize_unit() -> String);
+    unsupported_key!(serialize_unit_struct(&'static str) -> String);
+    unsupported_key!(serialize_seq(Option<usize>) -> Self::SerializeSeq);
+    unsupported_key!(serialize_tuple(usize) -> Self::SerializeTuple);
+    unsupported_key!(serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct);
+    unsupported_key!(serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant);
+    unsupported_key!(serialize_map(Option<usize>) -> Self::SerializeMap);
+    unsupported_key!(serialize_struct(&'static str, usize) -> Self::SerializeStruct);
+    unsupported_key!(serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant);
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,118 @@
+use super::*;
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use crate::serde::{de, Header};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Sample {
+    real: f32,
+    int: i32,
+    string: String,
+    #[serde(default)]
+    yes: bool,
+    #[serde(default)]
+    no: bool,
+    #[serde(default)]
+    missing: Option<i32>,
+    list: Vec<Entry>,
+    map: BTreeMap<i32, String>
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Entry {
+    name: String
+}
+
+fn sample() -> Sample {
+    Sample {
+        real: 1.5,
+        int: 52363,
+        string: String::from("Lorem <ipsum>"),
+        yes: true,
+        no: false,
+        missing: None,
+        list: vec![Entry { name: String::from("a") }, Entry { name: String::from("b") }],
+        map: BTreeMap::from([(3, String::from("three"))])
+    }
+}
+
+#[test]
+fn writes_plist_xml() {
+    let xml = to_xml(&DataWithHeader::new(sample())).unwrap();
+    assert_eq!(
+        String::from_utf8(xml).unwrap(),
+        concat!(
+            r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+            "<k>real</k><r>1.5</r><k>int</k><i>52363</i><k>string</k><s>Lorem &lt;ipsum&gt;</s>",
+            "<k>yes</k><t/>",
+            "<k>list</k><d><k>_isArr</k><t/><k>k_0</k><d><k>name</k><s>a</s></d><k>k_1</k><d><k>name</k><s>b</s></d></d>",
+            "<k>map</k><d><k>3</k><s>three</s></d>",
+            "</dict></plist>"
+        )
+    );
+}
+
+#[test]
+fn round_trips_encoded_data() {
+    let encoded = to_vec(&DataWithHeader::new(sample())).unwrap();
+    let decoded: DataWithHeader<Sample> = de::from_reader(&encoded[..]).unwrap();
+    assert_eq!(decoded.t, sample());
+    assert_eq!(decoded.header, Header::default());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Omitted {
+    options: Vec<Option<i32>>,
+    flags: Vec<bool>
+}
+
+#[test]
+fn keeps_indices_of_omitted_elements() {
+    let omitted = Omitted { options: vec![Some(1), None, Some(2)], flags: vec![true, false, true] };
+    let xml = String::from_utf8(to_xml(&DataWithHeader::new(&omitted)).unwrap()).unwrap();
+    assert!(xml.contains("<k>k_0</k><i>1</i><k>k_2</k><i>2</i>"), "{xml}");
+    assert!(xml.contains("<k>k_0</k><t/><k>k_2</k><t/>"), "{xml}");
+
+    let encoded = to_vec(&DataWithHeader::new(&omitted)).unwrap();
+    let decoded: DataWithHeader<Omitted> = de::from_reader(&encoded[..]).unwrap();
+    assert_eq!(decoded.t, omitted);
+}
+
+#[test]
+fn round_trips_empty_strings() {
+    let sample = Sample { string: String::new(), list: vec![Entry { name: String::new() }], ..sample() };
+    let encoded = to_vec(&DataWithHeader::new(sample)).unwrap();
+    let decoded: DataWithHeader<Sample> = de::from_reader(&encoded[..]).unwrap();
+    assert_eq!(decoded.t.string, "");
+    assert_eq!(decoded.t.list[0].name, "");
+}
+
+/// Map written with separate `serialize_key` and `serialize_value` calls
+struct SplitEntries(&'static [(&'static str, i32)]);
+
+impl Serialize for SplitEntries {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in self.0 {
+            map.serialize_key(key)?;
+            map.serialize_value(value)?;
+        }
+        map.end()
+    }
+}
+
+#[test]
+fn writes_maps_with_split_entries() {
+    let xml = to_xml(&DataWithHeader::new(SplitEntries(&[("a", 1), ("b", 2)]))).unwrap();
+    let read: BTreeMap<String, i32> = de::from_any_reader(xml.as_slice()).unwrap().t;
+    assert_eq!(read, BTreeMap::from([(String::from("a"), 1), (String::from("b"), 2)]));
+}
+
+#[test]
+fn rejects_map_values_without_keys() {
+    use serde::ser::SerializeMap;
+    let mut serializer = Serializer::new();
+    let mut map = serde::Serializer::serialize_map(&mut serializer, None).unwrap();
+    assert!(matches!(map.serialize_value(&1), Err(SerError::Custom(_))));
+}
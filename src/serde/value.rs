@@ -0,0 +1,228 @@
+use std::{collections::BTreeMap, fmt};
+use serde::{
+    de::{self, IntoDeserializer},
+    ser::{self, SerializeMap, SerializeSeq},
+    forward_to_deserialize_any
+};
+use super::error::{DeError, DeResult};
+
+/// Any value a save can contain, used to keep data no typed struct models
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Dict(BTreeMap<String, Value>),
+    Array(Vec<Value>),
+    String(String),
+    Integer(i64),
+    Real(f64),
+    Bool(bool)
+}
+
+impl Value {
+    pub fn as_dict(&self) -> Option<&BTreeMap<String, Value>> {
+        if let Self::Dict(dict) = self { Some(dict) } else { None }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        if let Self::Array(array) = self { Some(array) } else { None }
+    }
+
+    /// Dict entry, `None` for other values
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_dict().and_then(|dict| dict.get(key))
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Dict(dict) => write!(f, "<dict of {}>", dict.len()),
+            Self::Array(array) => write!(f, "<array of {}>", array.len()),
+            Self::String(text) => write!(f, "{text:?}"),
+            Self::Integer(int) => write!(f, "{int}"),
+            Self::Real(real) => write!(f, "{real}"),
+            Self::Bool(bool) => write!(f, "{bool}")
+        }
+    }
+}
+
+impl ser::Serialize for Value {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Dict(dict) => {
+                let mut map = serializer.serialize_map(Some(dict.len()))?;
+                for (key, value) in dict {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Self::Array(array) => {
+                let mut seq = serializer.serialize_seq(Some(array.len()))?;
+                for value in array {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Self::String(text) => serializer.serialize_str(text),
+            Self::Integer(int) => serializer.serialize_i64(*int),
+            Self::Real(real) => serializer.serialize_f64(*real),
+            Self::Bool(bool) => serializer.serialize_bool(*bool)
+        }
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> de::Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any save value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Integer(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        match i64::try_from(v) {
+            Ok(v) => Ok(Value::Integer(v)),
+            Err(_) => Ok(Value::Real(v as f64))
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Real(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut array = vec![];
+        while let Some(value) = seq.next_element()? {
+            array.push(value);
+        }
+        Ok(Value::Array(array))
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut dict = BTreeMap::new();
+        while let Some((key, value)) = map.next_entry()? {
+            dict.insert(key, value);
+        }
+        Ok(Value::Dict(dict))
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Value {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// Deserializes a typed struct out of a value, as leniently as the plist
+/// deserializer treats numbers stored as strings
+pub fn from_value<T: de::DeserializeOwned>(value: Value) -> DeResult<T> {
+    T::deserialize(value)
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_number {
+    ($deserialize: ident => $visit: ident) => {
+        fn $deserialize<V>(self, visitor: V) -> DeResult<V::Value>
+        where V: de::Visitor<'de> {
+            match self {
+                Value::String(text) => match text.parse() {
+                    Ok(parsed) => visitor.$visit(parsed),
+                    Err(_) => Err(DeError::Deserialization)
+                }
+                value => value.deserialize_any(visitor)
+            }
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = DeError;
+
+    fn deserialize_any<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        match self {
+            Value::Dict(dict) => visitor.visit_map(de::value::MapDeserializer::new(dict.into_iter())),
+            Value::Array(array) => visitor.visit_seq(de::value::SeqDeserializer::new(array.into_iter())),
+            Value::String(text) => visitor.visit_string(text),
+            Value::Integer(int) => visitor.visit_i64(int),
+            Value::Real(real) => visitor.visit_f64(real),
+            Value::Bool(bool) => visitor.visit_bool(bool)
+        }
+    }
+
+    deserialize_number!(deserialize_i8 => visit_i8);
+    deserialize_number!(deserialize_i16 => visit_i16);
+    deserialize_number!(deserialize_i32 => visit_i32);
+    deserialize_number!(deserialize_i64 => visit_i64);
+    deserialize_number!(deserialize_u8 => visit_u8);
+    deserialize_number!(deserialize_u16 => visit_u16);
+    deserialize_number!(deserialize_u32 => visit_u32);
+    deserialize_number!(deserialize_u64 => visit_u64);
+    deserialize_number!(deserialize_f32 => visit_f32);
+    deserialize_number!(deserialize_f64 => visit_f64);
+
+    fn deserialize_str<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        match self {
+            Value::Integer(int) => visitor.visit_string(int.to_string()),
+            Value::Real(real) => visitor.visit_string(real.to_string()),
+            value => value.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> DeResult<V::Value>
+    where V: de::Visitor<'de> {
+        match self {
+            Value::String(text) => visitor.visit_enum(text.into_deserializer()),
+            _ => Err(DeError::Deserialization)
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i128 u128 char bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf}
+};
+use serde::{Serialize, Deserialize};
+use crate::{
+    local_levels::{LocalLevelsDB, Level},
+    requests::decode_component,
+    serde::{
+        Value,
+        robtop::{self, Separators},
+        error::{DeError, DeResult, SerResult}
+    }
+};
+
+/// Metadata of a custom song, as stored in CCGameManager.dat and sent with
+/// `~|~` separated keys by getGJSongInfo
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SongInfo {
+    #[serde(rename = "kCEK")]
+    #[serde(default)]
+    object_type: Option<i32>,
+    #[serde(rename = "1")]
+    pub id: i32,
+    #[serde(rename = "2")]
+    #[serde(deserialize_with = "robtop::deserialize_string")]
+    pub name: String,
+    #[serde(rename = "3")]
+    #[serde(default)]
+    pub artist_id: i32,
+    #[serde(rename = "4")]
+    #[serde(deserialize_with = "robtop::deserialize_string")]
+    pub artist: String,
+    #[serde(rename = "5")]
+    pub size_mb: f32,
+    /// URL encoded, as the servers send it
+    #[serde(rename = "10")]
+    #[serde(default, deserialize_with = "robtop::deserialize_string")]
+    pub url: String,
+    /// Keys not modeled above, kept so that saving doesn't drop them
+    #[serde(flatten)]
+    other: BTreeMap<String, Value>
+}
+
+impl SongInfo {
+    /// Reads a getGJSongInfo response, which is `-1` for songs that don't
+    /// exist and `-2` for songs that aren't allowed
+    pub fn from_response(response: &str) -> DeResult<Self> {
+        match response.trim() {
+            "-1" => Err(DeError::Custom(String::from("song not found"))),
+            "-2" => Err(DeError::Custom(String::from("song not allowed"))),
+            response => robtop::from_str_with(response, Separators::SONG)
+        }
+    }
+
+    /// The response `from_response` reads
+    pub fn to_response(&self) -> SerResult<String> {
+        robtop::to_string_with(&Self { object_type: None, ..self.clone() }, Separators::SONG)
+    }
+
+    /// Decoded `url`
+    pub fn download_url(&self) -> String {
+        decode_component(&self.url)
+    }
+
+    /// Name of the file the game downloads the song to
+    pub fn file_name(&self) -> String {
+        song_file_name(self.id)
+    }
+}
+
+/// Downloaded custom songs, stored under `MDLM_001` in CCGameManager.dat
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct SongLibrary {
+    songs: HashMap<i32, SongInfo>
+}
+
+impl SongLibrary {
+    pub fn get(&self, id: i32) -> Option<&SongInfo> {
+        self.songs.get(&id)
+    }
+
+    pub fn contains(&self, id: i32) -> bool {
+        self.songs.contains_key(&id)
+    }
+
+    pub fn insert(&mut self, song: SongInfo) -> Option<SongInfo> {
+        self.songs.insert(song.id, song)
+    }
+
+    pub fn songs(&self) -> impl Iterator<Item = &SongInfo> {
+        self.songs.values()
+    }
+
+    /// Downloaded custom song the level uses, `None` if it uses an official
+    /// song or the game doesn't have it
+    pub fn level_song(&self, level: &Level) -> Option<&SongInfo> {
+        level.custom_song_id().and_then(|id| self.get(id))
+    }
+
+    /// Local levels using a custom song the game hasn't downloaded, paired
+    /// with the song ID
+    pub fn missing_songs<'a>(&self, levels: &'a LocalLevelsDB) -> Vec<(&'a Level, i32)> {
+        levels.levels().iter()
+            .filter_map(|level| level.custom_song_id().map(|id| (level, id)))
+            .filter(|(_, id)| !self.contains(*id))
+            .collect()
+    }
+}
+
+/// `<id>.mp3`, name of the file the game stores the song as
+pub fn song_file_name(id: i32) -> String {
+    format!("{id}.mp3")
+}
+
+/// Expected location of the song in the game's data directory
+pub fn song_path<P: AsRef<Path>>(data_dir: P, id: i32) -> PathBuf {
+    data_dir.as_ref().join(song_file_name(id))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,85 @@
+use super::*;
+use crate::serde::from_any_reader;
+
+const RESPONSE: &str = concat!(
+    "1~|~803223~|~2~|~Supernova~|~3~|~12~|~4~|~Xtrullor~|~5~|~9.56",
+    "~|~10~|~https%3A%2F%2Faudio.ngfiles.com%2F803000%2F803223_Supernova.mp3%3Ff1"
+);
+
+/// `MDLM_001` of CCGameManager.dat
+const MDLM_001: &str = concat!(
+    r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict><k>803223</k><d>"#,
+    "<k>kCEK</k><i>6</i><k>1</k><i>803223</i><k>2</k><s>Supernova</s><k>3</k><i>12</i>",
+    "<k>4</k><s>Xtrullor</s><k>5</k><r>9.56</r>",
+    "<k>10</k><s>https%3A%2F%2Faudio.ngfiles.com%2F803000%2F803223_Supernova.mp3%3Ff1</s>",
+    "</d></dict></plist>"
+);
+
+#[test]
+fn parses_and_writes_song_info() {
+    let response = format!("{RESPONSE}~|~6~|~~|~7~|~~|~8~|~1");
+    let song = SongInfo::from_response(&response).unwrap();
+    assert_eq!((song.id, song.name.as_str(), song.artist.as_str(), song.size_mb), (803223, "Supernova", "Xtrullor", 9.56));
+    assert_eq!(song.download_url(), "https://audio.ngfiles.com/803000/803223_Supernova.mp3?f1");
+    assert_eq!(song.to_response().unwrap(), response);
+    let numeric = SongInfo::from_response("1~|~1~|~2~|~1000~|~4~|~2~|~5~|~1").unwrap();
+    assert_eq!((numeric.name.as_str(), numeric.artist.as_str(), numeric.size_mb), ("1000", "2", 1.0));
+    assert!(SongInfo::from_response("-1").is_err());
+    assert!(SongInfo::from_response("-2").is_err());
+}
+
+#[test]
+fn resolves_level_songs() {
+    let library: SongLibrary = from_any_reader(MDLM_001.as_bytes()).unwrap().t;
+    assert_eq!(library.get(803223).unwrap().to_response().unwrap(), RESPONSE);
+
+    let level: Level = robtop::from_str("k2:Uses it:k45:803223").unwrap();
+    assert_eq!(library.level_song(&level).map(|song| song.artist.as_str()), Some("Xtrullor"));
+    let official: Level = robtop::from_str("k2:Official:k8:3").unwrap();
+    assert_eq!(library.level_song(&official), None);
+    let missing: Level = robtop::from_str("k2:Missing:k45:1").unwrap();
+    assert_eq!(library.level_song(&missing), None);
+}
+
+
+#[test]
+fn parses_song_library() {
+    let xml = MDLM_001.replace("</dict></plist>", concat!(
+        "<k>71</k><d><k>kCEK</k><i>6</i><k>1</k><i>71</i><k>2</k><s>Theory</s>",
+        "<k>4</k><s>DJVI</s><k>5</k><r>4.2</r></d></dict></plist>"
+    ));
+    let library: SongLibrary = from_any_reader(xml.as_bytes()).unwrap().t;
+    let mut ids: Vec<_> = library.songs().map(|song| song.id).collect();
+    ids.sort();
+    assert_eq!(ids, [71, 803223]);
+    let song = library.get(71).unwrap();
+    assert_eq!((song.name.as_str(), song.artist.as_str(), song.size_mb, song.url.as_str()), ("Theory", "DJVI", 4.2, ""));
+    assert!(library.contains(803223));
+    assert!(!library.contains(1));
+}
+
+#[test]
+fn finds_missing_songs() {
+    let library: SongLibrary = from_any_reader(MDLM_001.as_bytes()).unwrap().t;
+    let local_levels: LocalLevelsDB = from_any_reader(concat!(
+        r#"<?xml version="1.0"?><plist version="1.0" gjver="2.0"><dict>"#,
+        "<k>LLM_01</k><d><k>_isArr</k><t/>",
+        "<k>k_0</k><d><k>k2</k><s>Downloaded</s><k>k45</k><i>803223</i></d>",
+        "<k>k_1</k><d><k>k2</k><s>Missing</s><k>k45</k><i>1</i></d>",
+        "<k>k_2</k><d><k>k2</k><s>Official</s><k>k8</k><i>3</i></d>",
+        "</d><k>LLM_02</k><i>42</i>",
+        "</dict></plist>"
+    ).as_bytes()).unwrap().t;
+    let missing: Vec<_> = library.missing_songs(&local_levels).into_iter()
+        .map(|(level, id)| (level.name(), id))
+        .collect();
+    assert_eq!(missing, [("Missing", 1)]);
+}
+
+#[test]
+fn names_song_files() {
+    assert_eq!(song_file_name(803223), "803223.mp3");
+    let library: SongLibrary = from_any_reader(MDLM_001.as_bytes()).unwrap().t;
+    assert_eq!(library.get(803223).unwrap().file_name(), "803223.mp3");
+    assert_eq!(song_path("/data", 71), Path::new("/data/71.mp3"));
+}
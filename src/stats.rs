@@ -0,0 +1,57 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompletedLevelKind {
+    /// Official level
+    Main,
+    Online,
+    Daily,
+    Gauntlet
+}
+
+impl CompletedLevelKind {
+    pub fn key_prefix(self) -> &'static str {
+        match self {
+            Self::Main => "n",
+            Self::Online => "c",
+            Self::Daily => "d",
+            Self::Gauntlet => "g"
+        }
+    }
+}
+
+/// Entry of `GS_completed`, stored as `<kind prefix>_<level id>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompletedLevel {
+    pub kind: CompletedLevelKind,
+    pub id: i32
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCompletionKey;
+
+impl FromStr for CompletedLevel {
+    type Err = InvalidCompletionKey;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (prefix, id) = s.split_once('_').ok_or(InvalidCompletionKey)?;
+        let kind = match prefix {
+            "n" => CompletedLevelKind::Main,
+            "c" => CompletedLevelKind::Online,
+            "d" => CompletedLevelKind::Daily,
+            "g" => CompletedLevelKind::Gauntlet,
+            _ => return Err(InvalidCompletionKey)
+        };
+        let id = id.parse().map_err(|_| InvalidCompletionKey)?;
+        Ok(Self { kind, id })
+    }
+}
+
+impl Display for CompletedLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}_{}", self.kind.key_prefix(), self.id)
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::{HashMap, BTreeSet};
+
+/// Category of an unlockable cosmetic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnlockKind {
+    Cube,
+    Ship,
+    Ball,
+    Ufo,
+    Wave,
+    Robot,
+    Spider,
+    /// Added in 2.2
+    Swing,
+    /// Added in 2.2
+    Jetpack,
+    Trail,
+    DeathEffect,
+    Color,
+    SecondaryColor
+}
+
+impl UnlockKind {
+    pub const ALL: [UnlockKind; 13] = [
+        Self::Cube,
+        Self::Ship,
+        Self::Ball,
+        Self::Ufo,
+        Self::Wave,
+        Self::Robot,
+        Self::Spider,
+        Self::Swing,
+        Self::Jetpack,
+        Self::Trail,
+        Self::DeathEffect,
+        Self::Color,
+        Self::SecondaryColor
+    ];
+
+    /// Prefix of the value keeper keys, the full key is `<prefix>_<id>`
+    pub fn key_prefix(self) -> &'static str {
+        match self {
+            Self::Cube => "i",
+            Self::Ship => "ship",
+            Self::Ball => "ball",
+            Self::Ufo => "bird",
+            Self::Wave => "dart",
+            Self::Robot => "robot",
+            Self::Spider => "spider",
+            Self::Swing => "swing",
+            Self::Jetpack => "jetpack",
+            Self::Trail => "special",
+            Self::DeathEffect => "death",
+            Self::Color => "c0",
+            Self::SecondaryColor => "c1"
+        }
+    }
+
+    pub fn key(self, id: i32) -> String {
+        format!("{}_{id}", self.key_prefix())
+    }
+
+    /// Amount of items in the category as of 2.2
+    pub fn total(self) -> usize {
+        match self {
+            Self::Cube => 484,
+            Self::Ship => 169,
+            Self::Ball => 118,
+            Self::Ufo => 149,
+            Self::Wave => 96,
+            Self::Robot => 68,
+            Self::Spider => 69,
+            Self::Swing => 43,
+            Self::Jetpack => 8,
+            Self::Trail => 7,
+            Self::DeathEffect => 20,
+            Self::Color | Self::SecondaryColor => 107
+        }
+    }
+
+    /// Items every player has without unlocking them
+    pub fn is_default(self, id: i32) -> bool {
+        match self {
+            Self::Color => id == 0,
+            Self::SecondaryColor => id == 3,
+            _ => id == 1
+        }
+    }
+}
+
+/// Prefix of unlocked game variables, e.g. `ugv_17`
+const GAME_VARIABLE_PREFIX: &str = "ugv_";
+/// Prefix of unlocked game objects, e.g. `ugo_5`
+const GAME_OBJECT_PREFIX: &str = "ugo_";
+
+/// Read-only view over the unlock state stored in `valueKeeper` and
+/// `unlockValueKeeper`
+pub struct Unlocks<'a> {
+    value_keeper: &'a HashMap<String, String>,
+    unlock_value_keeper: &'a HashMap<String, String>
+}
+
+impl<'a> Unlocks<'a> {
+    pub(crate) fn new(
+        value_keeper: &'a HashMap<String, String>,
+        unlock_value_keeper: &'a HashMap<String, String>
+    ) -> Self {
+        Self { value_keeper, unlock_value_keeper }
+    }
+
+    fn is_set(&self, key: &str) -> bool {
+        [self.value_keeper, self.unlock_value_keeper].iter()
+            .any(|keeper| keeper.get(key).is_some_and(|value| is_truthy(value)))
+    }
+
+    pub fn is_unlocked(&self, kind: UnlockKind, id: i32) -> bool {
+        kind.is_default(id) || self.is_set(&kind.key(id))
+    }
+
+    /// Suffixes of the set keys starting with the prefix, in ascending order
+    fn set_suffixes(&self, prefix: &str) -> BTreeSet<&'a str> {
+        [self.value_keeper, self.unlock_value_keeper].into_iter()
+            .flatten()
+            .filter(|(_, value)| is_truthy(value))
+            .filter_map(|(key, _)| key.strip_prefix(prefix))
+            .collect()
+    }
+
+    /// IDs of all unlocked items in the category, in ascending order
+    pub fn unlocked(&self, kind: UnlockKind) -> impl Iterator<Item = i32> {
+        let mut ids: BTreeSet<i32> = self.set_suffixes(&format!("{}_", kind.key_prefix()))
+            .into_iter()
+            .filter_map(|id| id.parse().ok())
+            .collect();
+        ids.extend((0..kind.total() as i32).filter(|id| kind.is_default(*id)));
+        ids.into_iter()
+    }
+
+    /// Whether the `ugv_<id>` key is set. The IDs are kept as written since
+    /// what they unlock isn't modeled
+    pub fn is_game_variable_unlocked(&self, id: &str) -> bool {
+        self.is_set(&format!("{GAME_VARIABLE_PREFIX}{id}"))
+    }
+
+    /// IDs of the set `ugv_` keys, sorted as text
+    pub fn unlocked_game_variables(&self) -> impl Iterator<Item = &'a str> {
+        self.set_suffixes(GAME_VARIABLE_PREFIX).into_iter()
+    }
+
+    /// Whether the `ugo_<id>` key is set, see `is_game_variable_unlocked`
+    pub fn is_game_object_unlocked(&self, id: &str) -> bool {
+        self.is_set(&format!("{GAME_OBJECT_PREFIX}{id}"))
+    }
+
+    /// IDs of the set `ugo_` keys, sorted as text
+    pub fn unlocked_game_objects(&self) -> impl Iterator<Item = &'a str> {
+        self.set_suffixes(GAME_OBJECT_PREFIX).into_iter()
+    }
+
+    /// Unlocked and total item count of the category. Items of versions
+    /// newer than `UnlockKind::total` knows don't count past the total
+    pub fn progress(&self, kind: UnlockKind) -> (usize, usize) {
+        (self.unlocked(kind).count().min(kind.total()), kind.total())
+    }
+}
+
+fn is_truthy(value: &str) -> bool {
+    !matches!(value, "" | "0")
+}
+
+#[cfg(test)]
+mod tests;
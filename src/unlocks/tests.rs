@@ -0,0 +1,62 @@
+use super::*;
+
+fn keeper(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries.iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn queries_unlocks() {
+    let value_keeper = keeper(&[("ship_12", "1"), ("ship_4", "0"), ("c0_7", "1"), ("gv_0026", "1")]);
+    let unlock_value_keeper = keeper(&[("ship_3", "1"), ("bird_2", "1")]);
+    let unlocks = Unlocks::new(&value_keeper, &unlock_value_keeper);
+
+    assert!(unlocks.is_unlocked(UnlockKind::Ship, 12));
+    assert!(unlocks.is_unlocked(UnlockKind::Ship, 3));
+    assert!(!unlocks.is_unlocked(UnlockKind::Ship, 4));
+    assert!(unlocks.is_unlocked(UnlockKind::Ufo, 1));
+    assert!(!unlocks.is_unlocked(UnlockKind::Color, 3));
+    assert!(unlocks.is_unlocked(UnlockKind::SecondaryColor, 3));
+
+    assert_eq!(unlocks.unlocked(UnlockKind::Ship).collect::<Vec<_>>(), vec![1, 3, 12]);
+    assert_eq!(unlocks.unlocked(UnlockKind::Color).collect::<Vec<_>>(), vec![0, 7]);
+    assert_eq!(unlocks.progress(UnlockKind::Ufo), (2, 149));
+}
+
+#[test]
+fn queries_2_2_unlocks() {
+    let value_keeper = keeper(&[("swing_5", "1"), ("jetpack_3", "1"), ("jetpack_8", "1")]);
+    let unlock_value_keeper = keeper(&[("swing_12", "1")]);
+    let unlocks = Unlocks::new(&value_keeper, &unlock_value_keeper);
+
+    assert!(unlocks.is_unlocked(UnlockKind::Swing, 12));
+    assert!(!unlocks.is_unlocked(UnlockKind::Swing, 3));
+    assert_eq!(unlocks.unlocked(UnlockKind::Swing).collect::<Vec<_>>(), vec![1, 5, 12]);
+    assert_eq!(unlocks.progress(UnlockKind::Jetpack), (3, 8));
+    assert!(UnlockKind::ALL.contains(&UnlockKind::Jetpack));
+}
+
+#[test]
+fn caps_progress_at_the_total() {
+    let value_keeper = (2..=12).map(|id| (format!("jetpack_{id}"), String::from("1"))).collect();
+    let unlock_value_keeper = HashMap::new();
+    let unlocks = Unlocks::new(&value_keeper, &unlock_value_keeper);
+    assert_eq!(unlocks.unlocked(UnlockKind::Jetpack).count(), 12);
+    assert_eq!(unlocks.progress(UnlockKind::Jetpack), (8, 8));
+}
+
+#[test]
+fn queries_value_keeper_unlocks() {
+    let value_keeper = keeper(&[("ugv_17", "1"), ("ugv_3", "1"), ("ugv_5", "0"), ("ugo_12", "1"), ("gv_0026", "1")]);
+    let unlock_value_keeper = keeper(&[("ugo_4", "1"), ("ship_3", "1")]);
+    let unlocks = Unlocks::new(&value_keeper, &unlock_value_keeper);
+
+    assert!(unlocks.is_game_variable_unlocked("17"));
+    assert!(!unlocks.is_game_variable_unlocked("5"));
+    assert!(!unlocks.is_game_variable_unlocked("0026"));
+    assert!(unlocks.is_game_object_unlocked("4"));
+    assert_eq!(unlocks.unlocked_game_variables().collect::<Vec<_>>(), ["17", "3"]);
+    assert_eq!(unlocks.unlocked_game_objects().collect::<Vec<_>>(), ["12", "4"]);
+    assert_eq!(unlocks.unlocked(UnlockKind::Ship).collect::<Vec<_>>(), vec![1, 3]);
+}
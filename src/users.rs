@@ -0,0 +1,145 @@
+use serde::Deserialize;
+use crate::{
+    icons::{IconKit, GameMode, PlayerColor},
+    serde::{
+        robtop,
+        error::{DeError, DeResult}
+    }
+};
+
+/// Profile of getGJUserInfo20
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct UserProfile {
+    #[serde(rename = "1")]
+    pub name: String,
+    #[serde(rename = "2")]
+    pub player_id: i32,
+    #[serde(rename = "16")]
+    pub account_id: i32,
+    #[serde(rename = "3")]
+    #[serde(default)]
+    pub stars: i32,
+    #[serde(rename = "52")]
+    #[serde(default)]
+    pub moons: i32,
+    #[serde(rename = "46")]
+    #[serde(default)]
+    pub diamonds: i32,
+    #[serde(rename = "4")]
+    #[serde(default)]
+    pub demons: i32,
+    #[serde(rename = "13")]
+    #[serde(default)]
+    pub secret_coins: i32,
+    #[serde(rename = "17")]
+    #[serde(default)]
+    pub user_coins: i32,
+    #[serde(rename = "8")]
+    #[serde(default)]
+    pub creator_points: i32,
+    /// 0 for players outside of the leaderboard
+    #[serde(rename = "30")]
+    #[serde(default)]
+    pub global_rank: i32,
+    /// 1 for moderators, 2 for elder moderators
+    #[serde(rename = "49")]
+    #[serde(default)]
+    pub mod_level: i32,
+    /// Channel ID
+    #[serde(rename = "20")]
+    #[serde(default)]
+    pub youtube: Option<String>,
+    #[serde(rename = "44")]
+    #[serde(default)]
+    pub twitter: Option<String>,
+    #[serde(rename = "45")]
+    #[serde(default)]
+    pub twitch: Option<String>,
+    #[serde(rename = "21")]
+    #[serde(default)]
+    cube: i32,
+    #[serde(rename = "22")]
+    #[serde(default)]
+    ship: i32,
+    #[serde(rename = "23")]
+    #[serde(default)]
+    ball: i32,
+    #[serde(rename = "24")]
+    #[serde(default)]
+    ufo: i32,
+    #[serde(rename = "25")]
+    #[serde(default)]
+    wave: i32,
+    #[serde(rename = "26")]
+    #[serde(default)]
+    robot: i32,
+    #[serde(rename = "43")]
+    #[serde(default)]
+    spider: i32,
+    #[serde(rename = "53")]
+    #[serde(default)]
+    swing: i32,
+    #[serde(rename = "54")]
+    #[serde(default)]
+    jetpack: i32,
+    #[serde(rename = "48")]
+    #[serde(default)]
+    death_effect: i32,
+    #[serde(rename = "10")]
+    #[serde(default)]
+    color: i32,
+    #[serde(rename = "11")]
+    #[serde(default)]
+    color_secondary: i32,
+    #[serde(rename = "28")]
+    #[serde(default)]
+    glow: bool
+}
+
+impl UserProfile {
+    /// The `-1` the server answers with for unknown accounts is an error
+    pub fn from_response(response: &str) -> DeResult<Self> {
+        match response.trim() {
+            "-1" => Err(DeError::Custom(String::from("user not found"))),
+            response => robtop::from_str(response)
+        }
+    }
+
+    /// Icons as `GameManagerDB::icon_kit` stores them. Profiles show every
+    /// icon but not the trail nor which icon type is selected, so those are
+    /// left at 0 and cube
+    pub fn icon_kit(&self) -> IconKit {
+        IconKit {
+            cube: self.cube,
+            ship: self.ship,
+            ball: self.ball,
+            ufo: self.ufo,
+            wave: self.wave,
+            robot: self.robot,
+            spider: self.spider,
+            swing: self.swing,
+            jetpack: self.jetpack,
+            trail: 0,
+            death_effect: self.death_effect,
+            color: PlayerColor(self.color),
+            color_secondary: PlayerColor(self.color_secondary),
+            icon_type: GameMode::Cube,
+            glow: self.glow
+        }
+    }
+
+    pub fn youtube_url(&self) -> Option<String> {
+        self.youtube.as_ref().map(|id| format!("https://youtube.com/channel/{id}"))
+    }
+
+    pub fn twitter_url(&self) -> Option<String> {
+        self.twitter.as_ref().map(|name| format!("https://twitter.com/{name}"))
+    }
+
+    pub fn twitch_url(&self) -> Option<String> {
+        self.twitch.as_ref().map(|name| format!("https://twitch.tv/{name}"))
+    }
+}
+
+#[cfg(test)]
+mod tests;
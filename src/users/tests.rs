@@ -0,0 +1,28 @@
+use super::*;
+
+const RESPONSE: &str = concat!(
+    "1:Tester:2:3935672:13:149:17:42:10:3:11:12:51:12:3:12345:46:6789:4:120:8:3:",
+    "18:0:19:0:50:0:20:UCabc123:21:35:22:12:23:4:24:8:25:7:26:5:28:1:43:3:48:2:",
+    "30:1500:16:161:31:0:44::45:tester:49:1:29:1"
+);
+
+#[test]
+fn parses_profile() {
+    let profile = UserProfile::from_response(RESPONSE).unwrap();
+    assert_eq!((profile.name.as_str(), profile.player_id, profile.account_id), ("Tester", 3935672, 161));
+    assert_eq!((profile.stars, profile.diamonds, profile.demons, profile.moons), (12345, 6789, 120, 0));
+    assert_eq!((profile.global_rank, profile.mod_level), (1500, 1));
+    assert_eq!(profile.youtube_url().as_deref(), Some("https://youtube.com/channel/UCabc123"));
+    assert_eq!(profile.twitter_url(), None);
+    assert_eq!(profile.twitch_url().as_deref(), Some("https://twitch.tv/tester"));
+    assert!(UserProfile::from_response("-1").is_err());
+}
+
+#[test]
+fn icons_match_game_manager_fields() {
+    let icons = UserProfile::from_response(RESPONSE).unwrap().icon_kit();
+    assert_eq!((icons.cube, icons.ship, icons.ufo, icons.spider, icons.death_effect), (35, 12, 8, 3, 2));
+    assert_eq!((icons.color, icons.color_secondary), (PlayerColor(3), PlayerColor(12)));
+    assert_eq!(icons.main_icon(), 35);
+    assert!(icons.glow);
+}